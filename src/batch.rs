@@ -0,0 +1,88 @@
+use crate::convert::{Converter, Format, Frame};
+use crate::error::Glif2SvgError;
+use crate::render;
+use crate::ufo::Ufo;
+
+use glifparser;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where the output in `format` for a glif filename goes, e.g. `A_.glif` → `outdir/A_.png`.
+pub fn output_path(outdir: &Path, glif_filename: &str, format: Format) -> PathBuf {
+    outdir.join(Path::new(glif_filename).with_extension(format.extension()))
+}
+
+/// Converts every glyph listed in the UFO's `contents.plist` into `outdir`, one file per glif, using
+/// `jobs` threads (0 for one per CPU core). Glyphs that fail are reported and skipped.
+///
+/// Metrics should already have been resolved (see [`Converter::resolve_metrics`]), else they're
+/// queried once per glyph.
+pub fn convert_ufo(converter: &Converter, ufo: &Ufo, outdir: &Path, jobs: usize) -> Result<(), Glif2SvgError> {
+    fs::create_dir_all(outdir).map_err(|e|Glif2SvgError::from_io(outdir, e))?;
+
+    // One glyph failing doesn't stop the others.
+    let failed = thread_pool(jobs)?.install(|| {
+        ufo.contents.par_iter().filter(|(_name, filename)| {
+            let glif_path = ufo.glif_path(filename);
+            match convert_glif(converter, &glif_path, &output_path(outdir, filename, converter.options.format)) {
+                Ok(()) => false,
+                Err(e) => {
+                    eprintln!("{}", e.at(&glif_path));
+                    true
+                }
+            }
+        }).count()
+    });
+
+    if failed > 0 {
+        return Err(Glif2SvgError::Batch { failed, total: ufo.contents.len() })
+    }
+    Ok(())
+}
+
+/// Converts every glyph listed in the UFO's `contents.plist` into one PDF at `out`, a page per
+/// glyph in that order. Glyphs that fail are reported and left out, as in [`convert_ufo`].
+pub fn convert_ufo_pdf(converter: &Converter, ufo: &Ufo, out: &Path, jobs: usize) -> Result<(), Glif2SvgError> {
+    let frames: Vec<Option<Frame>> = thread_pool(jobs)?.install(|| {
+        ufo.contents.par_iter().map(|(_name, filename)| {
+            let glif_path = ufo.glif_path(filename);
+            let frame = read_glif(&glif_path).and_then(|glif|converter.frame(&glif));
+            frame.map_err(|e|eprintln!("{}", e.at(&glif_path))).ok()
+        }).collect()
+    });
+    let failed = frames.iter().filter(|f|f.is_none()).count();
+    let frames: Vec<Frame> = frames.into_iter().flatten().collect();
+
+    let pdf = render::pdf(&frames, converter.options.open_stroke_width)?;
+    fs::write(out, pdf).map_err(|e|Glif2SvgError::from_io(out, e))?;
+
+    if failed > 0 {
+        return Err(Glif2SvgError::Batch { failed, total: ufo.contents.len() })
+    }
+    Ok(())
+}
+
+fn thread_pool(jobs: usize) -> Result<ThreadPool, Glif2SvgError> {
+    ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .map_err(|e|Glif2SvgError::Usage(format!("Can't start {} threads: {}", jobs, e)))
+}
+
+/// Reads a glif, failing with a [`Glif2SvgError`] naming it.
+pub fn read_glif(glif_path: &Path) -> Result<glifparser::Glif<()>, Glif2SvgError> {
+    if let Err(e) = fs::metadata(glif_path) {
+        return Err(Glif2SvgError::from_io(glif_path, e))
+    }
+    glifparser::glif::read_from_filename(glif_path)
+        .map_err(|e|Glif2SvgError::Parse { path: Some(glif_path.to_owned()), message: format!("{:?}", e) })
+}
+
+fn convert_glif(converter: &Converter, glif_path: &Path, out: &Path) -> Result<(), Glif2SvgError> {
+    let glif = read_glif(glif_path)?;
+    let converted = converter.to_bytes(&glif).map_err(|e|e.at(glif_path))?;
+    fs::write(out, converted).map_err(|e|Glif2SvgError::from_io(out, e))
+}
@@ -0,0 +1,144 @@
+use crate::ufo::Ufo;
+
+use glifparser;
+use glifparser::{Handle, Outline};
+use glifparser::GlifComponent;
+
+use std::collections::HashMap;
+
+/// A UFO component transformation, `x' = xx·x + yx·y + dx` and `y' = xy·x + yy·y + dy`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine {
+    pub xx: f32,
+    pub xy: f32,
+    pub yx: f32,
+    pub yy: f32,
+    pub dx: f32,
+    pub dy: f32,
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Affine::IDENTITY
+    }
+}
+
+impl Affine {
+    pub const IDENTITY: Affine = Affine { xx: 1., xy: 0., yx: 0., yy: 1., dx: 0., dy: 0. };
+
+    pub fn from_component(component: &GlifComponent) -> Self {
+        Affine {
+            xx: f32::from(component.xScale),
+            xy: f32::from(component.xyScale),
+            yx: f32::from(component.yxScale),
+            yy: f32::from(component.yScale),
+            dx: f32::from(component.xOffset),
+            dy: f32::from(component.yOffset),
+        }
+    }
+
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.xx * x + self.yx * y + self.dx, self.xy * x + self.yy * y + self.dy)
+    }
+
+    /// `self` applied after `inner`, i.e. the transform of a component nested in a component.
+    pub fn compose(&self, inner: &Affine) -> Affine {
+        let (dx, dy) = self.apply(inner.dx, inner.dy);
+        Affine {
+            xx: self.xx * inner.xx + self.yx * inner.xy,
+            xy: self.xy * inner.xx + self.yy * inner.xy,
+            yx: self.xx * inner.yx + self.yx * inner.yy,
+            yy: self.xy * inner.yx + self.yy * inner.yy,
+            dx,
+            dy,
+        }
+    }
+
+    pub fn transform_outline(&self, outline: &Outline<()>) -> Outline<()> {
+        let handle = |h: Handle| match h {
+            Handle::At(x, y) => {
+                let (x, y) = self.apply(x, y);
+                Handle::At(x, y)
+            }
+            Handle::Colocated => Handle::Colocated,
+        };
+        outline.iter().map(|contour| {
+            contour.iter().map(|point| {
+                let mut point = point.clone();
+                (point.x, point.y) = self.apply(point.x, point.y);
+                point.a = handle(point.a);
+                point.b = handle(point.b);
+                point
+            }).collect()
+        }).collect()
+    }
+}
+
+/// Reads component base glifs from a UFO, each at most once.
+pub struct ComponentResolver<'a> {
+    ufo: &'a Ufo,
+    cache: HashMap<String, Option<glifparser::Glif<()>>>,
+}
+
+impl<'a> ComponentResolver<'a> {
+    pub fn new(ufo: &'a Ufo) -> Self {
+        ComponentResolver { ufo, cache: HashMap::new() }
+    }
+
+    /// The base glif of a component, or `None` (with a warning) if the UFO doesn't have it.
+    pub fn base(&mut self, name: &str) -> Option<&glifparser::Glif<()>> {
+        let ufo = self.ufo;
+        self.cache.entry(name.to_owned()).or_insert_with(|| {
+            let path = match ufo.glif_path_of(name) {
+                Some(path) => path,
+                None => {
+                    eprintln!("Component base {} not in {}", name, ufo.glyphs_dir.display());
+                    return None
+                }
+            };
+            match glifparser::glif::read_from_filename(&path) {
+                Ok(glif) => Some(glif),
+                Err(e) => {
+                    eprintln!("Failed to read component base {}: {:?}", path.display(), e);
+                    None
+                }
+            }
+        }).as_ref()
+    }
+
+    /// The glif's own contours followed by those of all of its components, transformed, recursively.
+    pub fn flattened_outline(&mut self, glif: &glifparser::Glif<()>) -> Outline<()> {
+        let mut outline = glif.outline.clone().unwrap_or_default();
+        outline.extend(self.components_outline(glif));
+        outline
+    }
+
+    /// Only the contours of the glif's components, transformed, recursively.
+    pub fn components_outline(&mut self, glif: &glifparser::Glif<()>) -> Outline<()> {
+        let mut outline = vec![];
+        self.flatten_components_into(&mut outline, glif, Affine::IDENTITY, &mut vec![glif.name.clone()]);
+        outline
+    }
+
+    /// `expanding` is the glyphs whose components are being flattened, outermost first. A
+    /// component of one of them is its own base, at some remove, and is left out.
+    fn flatten_components_into(&mut self, outline: &mut Outline<()>, glif: &glifparser::Glif<()>, transform: Affine, expanding: &mut Vec<String>) {
+        for component in glif.components.vec.iter() {
+            if expanding.contains(&component.base) {
+                eprintln!("Component {} of {} is its own base ({} → {}), left out", component.base, glif.name, expanding.join(" → "), component.base);
+                continue
+            }
+            let base = match self.base(&component.base) {
+                Some(base) => base.clone(),
+                None => continue,
+            };
+            let transform = transform.compose(&Affine::from_component(component));
+            if let Some(base_outline) = base.outline.as_ref() {
+                outline.extend(transform.transform_outline(base_outline));
+            }
+            expanding.push(component.base.clone());
+            self.flatten_components_into(outline, &base, transform, expanding);
+            expanding.pop();
+        }
+    }
+}
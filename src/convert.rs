@@ -0,0 +1,575 @@
+use crate::components::{Affine, ComponentResolver};
+use crate::error::Glif2SvgError;
+use crate::fontinfo::FontInfo;
+use crate::guides;
+use crate::pen::{is_open, PenError, SVGPathPen, DEFAULT_PRECISION};
+use crate::render;
+use crate::svg_boilerplate::*;
+use crate::ufo::Ufo;
+
+use glifparser;
+use mfek_ipc::{self, IPCInfo};
+use xmltree;
+
+use std::path::PathBuf;
+use std::str as stdstr;
+
+/// How the size of the SVG canvas is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Derivative)]
+#[derivative(Default)]
+pub enum ViewBoxMode {
+    /// `viewBox="minx miny width height"`
+    #[derivative(Default)]
+    ViewBox,
+    /// `width="…px" height="…px"`, no viewBox
+    WidthHeight,
+}
+
+/// How components are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Derivative)]
+#[derivative(Default)]
+pub enum ComponentMode {
+    /// Copy the base glyphs' contours into the glyph's path.
+    #[derivative(Default)]
+    Flatten,
+    /// Each base glyph becomes a `<symbol id="glyph-…">` in `<defs>`, each component a `<use>` of it.
+    Use,
+}
+
+/// What a glyph is converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Derivative)]
+#[derivative(Default)]
+pub enum Format {
+    #[derivative(Default)]
+    Svg,
+    /// Drawn by skia onto a raster surface.
+    Png,
+    /// Drawn by skia into a PDF, a page per glyph.
+    Pdf,
+}
+
+impl Format {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Svg => "svg",
+            Format::Png => "png",
+            Format::Pdf => "pdf",
+        }
+    }
+}
+
+/// Pixel size of raster output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RasterSize {
+    /// A font unit is a CSS pixel at this many DPI, 96 making it a pixel as in the SVG.
+    Dpi(f32),
+    /// The image is this many pixels high, its width following.
+    Height(u32),
+}
+
+impl Default for RasterSize {
+    fn default() -> Self {
+        RasterSize::Dpi(96.)
+    }
+}
+
+/// How the glif's points become path data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Derivative)]
+#[derivative(Default)]
+pub enum OutlineMode {
+    /// Through skia paths, as MFEK draws them.
+    #[derivative(Default)]
+    Skia,
+    /// Straight from the points, keeping quadratic (TrueType) contours quadratic with `Q`/`T`.
+    Native,
+}
+
+/// How the glyph's contours are split between `<path>`s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Derivative)]
+#[derivative(Default)]
+pub enum ContourMode {
+    /// All in one `<path>`.
+    #[derivative(Default)]
+    Joined,
+    /// A `<path id="contour-…">` per contour, numbered in the glif's order.
+    Split,
+}
+
+/// How the glif's anchors are written, if at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Derivative)]
+#[derivative(Default)]
+pub enum AnchorMode {
+    #[derivative(Default)]
+    Ignore,
+    /// A pair of named `sodipodi:guide`s crossing at each anchor.
+    Guides,
+    /// A small labelled `<circle>` on each anchor, in a layer of their own.
+    Markers,
+}
+
+/// Where the SVG's vertical metrics come from.
+#[derive(Debug, Clone, PartialEq, Derivative)]
+#[derivative(Default)]
+pub enum MetricsSource {
+    /// Don't consider the font's metrics, use the glyph's minx/maxx/miny/maxy.
+    #[derivative(Default)]
+    Bounds,
+    /// Ascender/descender already known to the caller.
+    Fixed { ascender: f64, descender: f64 },
+    /// Read the fontinfo.plist of the UFO the glif is in.
+    GlifPath(PathBuf),
+    /// Read this fontinfo.plist, for unparented glifs.
+    Fontinfo(PathBuf),
+    /// fontinfo.plist already read, e.g. once for a whole font by [`Converter::resolve_metrics`].
+    Resolved(FontInfo),
+}
+
+#[derive(Debug, Clone, Derivative)]
+#[derivative(Default(new="true"))]
+pub struct Options {
+    /// Decimal places of path data and canvas size, 0 for integers only.
+    #[derivative(Default(value="DEFAULT_PRECISION"))]
+    pub precision: u8,
+    pub viewbox: ViewBoxMode,
+    pub metrics: MetricsSource,
+    /// Ask MFEKmetadata if fontinfo.plist can't be read or lacks ascender/descender.
+    #[derivative(Default(value="true"))]
+    pub ipc_fallback: bool,
+    /// Where the base glyphs of components are found. Without it, components are left out.
+    pub ufo: Option<Ufo>,
+    pub components: ComponentMode,
+    pub outline: OutlineMode,
+    /// Write the shortest path data, as svgo would.
+    pub optimize_paths: bool,
+    pub contours: ContourMode,
+    pub anchors: AnchorMode,
+    /// Stroke of open contours, which aren't filled.
+    #[derivative(Default(value="String::from(\"black\")"))]
+    pub open_stroke: String,
+    #[derivative(Default(value="1."))]
+    pub open_stroke_width: f32,
+    pub format: Format,
+    pub raster_size: RasterSize,
+    /// Embed the glif, and mark each path with the `d` it was written with, so that svg2glif can
+    /// give back the glif exactly: point types, names and identifiers, components, `<lib>` and all.
+    pub round_trip: bool,
+}
+
+fn path_node(d: String) -> xmltree::XMLNode {
+    let mut pathxml = xmltree::Element::new("path");
+    pathxml.attributes.insert("d".to_owned(), d);
+    xmltree::XMLNode::Element(pathxml)
+}
+
+/// Records each path's `d` as written, so svg2glif can tell whether it was edited, and returns how
+/// many paths there are, so it can tell whether any were deleted. Components come back from the
+/// embedded glif, so their paths are left unmarked.
+fn mark_original_d(el: &mut xmltree::Element) -> usize {
+    if el.attributes.contains_key("mfek:components") {
+        return 0
+    }
+    let mut marked = 0;
+    if el.name == "path" {
+        if let Some(d) = el.attributes.get("d").cloned() {
+            el.attributes.insert("mfek:original-d".to_owned(), d);
+            marked += 1;
+        }
+    }
+    for child in el.children.iter_mut().filter_map(xmltree::XMLNode::as_mut_element) {
+        marked += mark_original_d(child);
+    }
+    marked
+}
+
+/// A glif framed as its SVG would be.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    /// Transforms glif coordinates to the SVG's.
+    pub pen: SVGPathPen,
+    /// `(minx, miny, width, height)`, as the viewBox.
+    pub view_box: (f64, f64, f64, f64),
+    /// The glif's outline, components flattened.
+    pub outline: glifparser::Outline<()>,
+}
+
+/// Converts glifs to SVG documents according to its [`Options`].
+#[derive(Debug, Clone, Default)]
+pub struct Converter {
+    pub options: Options,
+}
+
+impl Converter {
+    pub fn new(options: Options) -> Self {
+        Converter { options }
+    }
+
+    /// The font's metrics from fontinfo.plist, ascender and descender falling back to MFEKmetadata,
+    /// or `None` if the SVG should be framed by the glyph's bounds.
+    pub fn font_info(&self) -> Result<Option<FontInfo>, Glif2SvgError> {
+        let (fontinfo_path, path) = match &self.options.metrics {
+            MetricsSource::Bounds => return Ok(None),
+            MetricsSource::Fixed { ascender, descender } => {
+                return Ok(Some(FontInfo { ascender: Some(*ascender), descender: Some(*descender), ..FontInfo::default() }))
+            }
+            MetricsSource::Resolved(fontinfo) => return Ok(Some(fontinfo.clone())),
+            MetricsSource::GlifPath(path) => (FontInfo::path_for_glif(path), path),
+            MetricsSource::Fontinfo(path) => (Some(path.clone()), path),
+        };
+
+        let (mut fontinfo, why) = match fontinfo_path.as_ref().map(|p|FontInfo::from_file(p)) {
+            Some(Ok(fontinfo)) => (fontinfo, "fontinfo.plist has no ascender/descender".to_owned()),
+            Some(Err(e)) => (FontInfo::default(), format!("Failed to read fontinfo.plist ({})", e)),
+            None => (FontInfo::default(), "Not in a UFO, and no fontinfo.plist given".to_owned()),
+        };
+        if fontinfo.ascender_descender().is_none() {
+            let ipc_metrics = if self.options.ipc_fallback { self.ipc_metrics() } else { None };
+            let (ascender, descender) = ipc_metrics.ok_or_else(||Glif2SvgError::Metrics {
+                path: Some(fontinfo_path.unwrap_or_else(||path.clone())),
+                message: format!("{}, so the SVG can't be framed by the font's metrics (-M frames it by the glyph's bounds)", why),
+            })?;
+            fontinfo.ascender = Some(ascender);
+            fontinfo.descender = Some(descender);
+        }
+
+        Ok(Some(fontinfo))
+    }
+
+    /// Returns `(ascender, descender)`, or `None` if the SVG should be framed by the glyph's bounds.
+    pub fn metrics(&self) -> Result<Option<(f64, f64)>, Glif2SvgError> {
+        Ok(self.font_info()?.and_then(|fontinfo|fontinfo.ascender_descender()))
+    }
+
+    /// Asks MFEKmetadata, which must be in `$PATH`, for `(ascender, descender)`.
+    fn ipc_metrics(&self) -> Option<(f64, f64)> {
+        let ipc_info = match &self.options.metrics {
+            MetricsSource::GlifPath(path) => {
+                let path: &str = &path.to_string_lossy();
+                IPCInfo::from_glif_path("glif2svg".to_string(), &path)
+            }
+            MetricsSource::Fontinfo(path) => {
+                let path: &str = &path.to_string_lossy();
+                IPCInfo::from_fontinfo_path("glif2svg".to_string(), &path)
+            }
+            _ => return None,
+        };
+
+        if mfek_ipc::module::available("metadata".into(), "0.0.2-beta1").is_err() {
+            eprintln!("No fontinfo.plist metrics, and MFEKmetadata not available to ask for them");
+            return None
+        }
+
+        if let Ok((ascender, descender)) = mfek_ipc::helpers::metadata::ascender_descender(&ipc_info) {
+            Some((ascender as f64, descender as f64))
+        } else {
+            eprintln!("Failed to set metrics of SVG from glif font!");
+            None
+        }
+    }
+
+    /// Reads the font's metrics now and keeps them, so converting many glifs of one font doesn't
+    /// read fontinfo.plist once per glif.
+    pub fn resolve_metrics(&mut self) -> Result<(), Glif2SvgError> {
+        if let Some(fontinfo) = self.font_info()? {
+            self.options.metrics = MetricsSource::Resolved(fontinfo);
+        }
+        Ok(())
+    }
+
+    /// A pen writing path data as the options say, for documents that aren't framed by a glyph.
+    pub fn unframed_pen(&self) -> SVGPathPen {
+        let mut svg = SVGPathPen::new();
+        svg.precision = self.options.precision;
+        svg.native = self.options.outline == OutlineMode::Native;
+        svg.optimize = self.options.optimize_paths;
+        svg
+    }
+
+    /// The path data of a glif and, if there's a UFO to find them in, its components, as written by
+    /// `pen`.
+    pub fn path_data(&self, mut pen: SVGPathPen, glif: &glifparser::Glif<()>) -> Result<String, Glif2SvgError> {
+        pen.apply_outline(&self.flattened_outline(glif))?;
+        Ok(pen.path)
+    }
+
+    /// The glif's outline, and those of its components if there's a UFO to find them in.
+    pub fn flattened_outline(&self, glif: &glifparser::Glif<()>) -> glifparser::Outline<()> {
+        match self.options.ufo.as_ref() {
+            Some(ufo) if !glif.components.vec.is_empty() => ComponentResolver::new(ufo).flattened_outline(glif),
+            _ => glif.outline.clone().unwrap_or_default(),
+        }
+    }
+
+    /// The glif framed as its SVG would be, for drawing it otherwise.
+    pub fn frame(&self, glif: &glifparser::Glif<()>) -> Result<Frame, Glif2SvgError> {
+        let fontinfo = self.font_info()?;
+        let pen = self.pen(glif, fontinfo.as_ref());
+        let outline = self.flattened_outline(glif);
+        let mut svg = pen.clone();
+        svg.apply_outline(&outline)?;
+        let view_box = if fontinfo.is_some() { pen.viewBox() } else { svg.viewBox() };
+        Ok(Frame { pen, view_box, outline })
+    }
+
+    /// The glyph in the options' format.
+    pub fn to_bytes(&self, glif: &glifparser::Glif<()>) -> Result<Vec<u8>, Glif2SvgError> {
+        match self.options.format {
+            Format::Svg => Ok(self.to_svg(glif)?.into_bytes()),
+            Format::Png => render::png(self, glif),
+            Format::Pdf => render::pdf(&[self.frame(glif)?], self.options.open_stroke_width),
+        }
+    }
+
+    /// A pen framed by the glif's advance width and the font's metrics, if any.
+    fn pen(&self, glif: &glifparser::Glif<()>, fontinfo: Option<&FontInfo>) -> SVGPathPen {
+        let mut svg = self.unframed_pen();
+        svg.no_viewbox = self.options.viewbox == ViewBoxMode::WidthHeight;
+
+        if let Some(fontinfo) = fontinfo {
+            if let Some((ascender, descender)) = fontinfo.ascender_descender() {
+                svg.maxy = ascender;
+                svg.miny = descender;
+            }
+            svg.minx = 0.;
+            svg.maxx = glif.width.unwrap_or(0) as f64;
+        }
+
+        svg
+    }
+
+    pub fn to_element(&self, glif: &glifparser::Glif<()>) -> Result<xmltree::Element, Glif2SvgError> {
+        let fontinfo = self.font_info()?;
+        let mut svg = self.pen(glif, fontinfo.as_ref());
+        let frame_pen = svg.clone();
+        let frame = (svg.minx, svg.maxx, svg.miny, svg.maxy);
+
+        let mut resolver = match self.options.ufo.as_ref() {
+            Some(ufo) if !glif.components.vec.is_empty() => Some(ComponentResolver::new(ufo)),
+            _ => {
+                if !glif.components.vec.is_empty() {
+                    eprintln!("Not in a UFO, components of {} left out", glif.name);
+                }
+                None
+            }
+        };
+
+        let flattened;
+        let outline = match resolver.as_mut() {
+            Some(resolver) => {
+                flattened = resolver.flattened_outline(glif);
+                Some(&flattened)
+            }
+            None => glif.outline.as_ref(),
+        };
+
+        if let Some(o) = outline {
+            svg.apply_outline(o)?;
+        }
+
+        // With metrics, the frame is the font's, not the ink's.
+        if fontinfo.is_some() {
+            (svg.minx, svg.maxx, svg.miny, svg.maxy) = frame;
+        }
+
+        let mut svgxml = xmltree::Element::new("svg");
+        let mut namespace = xmltree::Namespace::empty();
+        for (k, v) in XMLNS.into_iter() {
+            namespace.put(*k, *v);
+        }
+        if self.options.round_trip {
+            namespace.put("mfek", MFEK_NS);
+        }
+        svgxml.namespaces = Some(namespace);
+        svgxml.attributes.insert("version".to_owned(), "1.1".to_owned());
+        if svg.no_viewbox {
+            for (k, v) in svg.px_size_attrs() {
+                svgxml.attributes.insert(k, v);
+            }
+        } else {
+            svgxml.attributes.insert("viewBox".to_owned(), svg.viewBox_str());
+        }
+
+        let mut sodipodixml = xmltree::Element::new(NAMEDVIEW_IDENT);
+        sodipodixml.attributes = NAMEDVIEW.into_iter().map(|(k, v)|((*k).to_owned(), (*v).to_owned())).collect();
+        let mut xygridxml = xmltree::Element::new(XYGRID_IDENT);
+        xygridxml.attributes = XYGRID.into_iter().map(|(k, v)|((*k).to_owned(), (*v).to_owned())).collect();
+        let mut guidexml = xmltree::Element::new("sodipodi:guide");
+        guidexml.attributes.insert("id".to_owned(), "baseline".to_owned());
+        // Also how svg2glif finds the baseline again, so it must be where the outline's y = 0 went.
+        let (_, baseline) = svg.page_position(frame_pen.transform_x(0.), frame_pen.transform_y(0.));
+        guidexml.attributes.insert("position".to_owned(), format!("{:.2},{:.2}", 0.0, baseline));
+        guidexml.attributes.insert("orientation".to_owned(), "0.00,1.00".to_owned());
+        sodipodixml.children = vec![xmltree::XMLNode::Element(xygridxml), xmltree::XMLNode::Element(guidexml)];
+        if let Some(fontinfo) = fontinfo.as_ref() {
+            sodipodixml.children.extend(guides::metric_guides(&svg, &frame_pen, fontinfo).into_iter().map(xmltree::XMLNode::Element));
+        }
+        sodipodixml.children.extend(guides::guideline_guides(&svg, &frame_pen, &glif.guidelines).into_iter().map(xmltree::XMLNode::Element));
+        if self.options.anchors == AnchorMode::Guides {
+            sodipodixml.children.extend(guides::anchor_guides(&svg, &frame_pen, &glif.anchors).into_iter().map(xmltree::XMLNode::Element));
+        }
+        svgxml.children.push(xmltree::XMLNode::Element(sodipodixml));
+
+        if self.options.round_trip {
+            match glifparser::glif::write(glif) {
+                Ok(glif_str) => {
+                    let mut glifxml = xmltree::Element::new("mfek:glif");
+                    glifxml.children.push(xmltree::XMLNode::Text(glif_str));
+                    let mut metadataxml = xmltree::Element::new("metadata");
+                    metadataxml.attributes.insert("id".to_owned(), "mfek-glif".to_owned());
+                    metadataxml.children.push(xmltree::XMLNode::Element(glifxml));
+                    svgxml.children.push(xmltree::XMLNode::Element(metadataxml));
+                }
+                Err(e) => eprintln!("Failed to embed {} for round-tripping: {:?}", glif.name, e),
+            }
+        }
+
+        let mut symbols = vec![];
+        let glyph_children = match resolver.as_mut() {
+            Some(resolver) if self.options.components == ComponentMode::Use => {
+                self.paths_and_uses(&frame_pen, resolver, glif, "", &mut symbols, &mut vec![glif.name.clone()])?
+            }
+            // Kept apart so svg2glif can leave them out, they come back as components.
+            Some(resolver) if self.options.round_trip => {
+                let mut nodes = match glif.outline.as_ref() {
+                    Some(o) => self.outline_paths(&frame_pen, o, "")?,
+                    None => vec![],
+                };
+                let mut componentsxml = xmltree::Element::new("g");
+                componentsxml.attributes.insert("id".to_owned(), "components".to_owned());
+                componentsxml.attributes.insert("mfek:components".to_owned(), "flattened".to_owned());
+                componentsxml.children = self.outline_paths(&frame_pen, &resolver.components_outline(glif), "components-")?;
+                nodes.push(xmltree::XMLNode::Element(componentsxml));
+                nodes
+            }
+            _ => match outline {
+                Some(o) => self.outline_paths(&frame_pen, o, "")?,
+                None => vec![],
+            },
+        };
+
+        if !symbols.is_empty() {
+            let mut defsxml = xmltree::Element::new("defs");
+            defsxml.children = symbols;
+            svgxml.children.push(xmltree::XMLNode::Element(defsxml));
+        }
+
+        let mut gxml = xmltree::Element::new("g");
+        gxml.attributes.insert("id".to_owned(), "glyph".to_owned());
+        gxml.children = glyph_children;
+        if self.options.round_trip {
+            let paths = mark_original_d(&mut gxml);
+            gxml.attributes.insert("mfek:paths".to_owned(), paths.to_string());
+        }
+        svgxml.children.push(xmltree::XMLNode::Element(gxml));
+
+        if self.options.anchors == AnchorMode::Markers && !glif.anchors.is_empty() {
+            svgxml.children.push(xmltree::XMLNode::Element(guides::anchor_markers(&frame_pen, &glif.anchors)));
+        }
+
+        Ok(svgxml)
+    }
+
+    /// The outline's closed contours as `<path>`s, and its open ones as `<path>`s in a group that
+    /// strokes rather than fills them. Contours that draw nothing are left out.
+    fn outline_paths(&self, frame_pen: &SVGPathPen, outline: &glifparser::Outline<()>, id_prefix: &str) -> Result<Vec<xmltree::XMLNode>, PenError> {
+        let (open, closed): (Vec<usize>, Vec<usize>) = (0..outline.len()).partition(|&i|is_open(&outline[i]));
+
+        let mut nodes = self.contour_paths(frame_pen, outline, &closed, id_prefix)?;
+        let open_nodes = self.contour_paths(frame_pen, outline, &open, id_prefix)?;
+        if !open_nodes.is_empty() {
+            let mut openxml = xmltree::Element::new("g");
+            openxml.attributes.insert("id".to_owned(), format!("{}open-contours", id_prefix));
+            openxml.attributes.insert("fill".to_owned(), "none".to_owned());
+            openxml.attributes.insert("stroke".to_owned(), self.options.open_stroke.clone());
+            openxml.attributes.insert("stroke-width".to_owned(), frame_pen.p(self.options.open_stroke_width).to_string());
+            openxml.children = open_nodes;
+            nodes.push(xmltree::XMLNode::Element(openxml));
+        }
+        Ok(nodes)
+    }
+
+    /// The contours of `outline` at `indices` as one `<path>`, or in [`ContourMode::Split`] a
+    /// `<path>` per contour with an `id` of `id_prefix` and its index.
+    fn contour_paths(&self, frame_pen: &SVGPathPen, outline: &glifparser::Outline<()>, indices: &[usize], id_prefix: &str) -> Result<Vec<xmltree::XMLNode>, PenError> {
+        let contours: glifparser::Outline<()> = indices.iter().map(|&i|outline[i].clone()).collect();
+        let mut svg = frame_pen.clone();
+        match self.options.contours {
+            ContourMode::Joined => {
+                svg.apply_outline(&contours)?;
+                Ok(if svg.path.is_empty() { vec![] } else { vec![path_node(svg.path)] })
+            }
+            ContourMode::Split => {
+                Ok(svg.apply_contours(&contours)?.into_iter().zip(indices).filter(|(d, _)|!d.is_empty()).map(|(d, i)| {
+                    let mut pathxml = xmltree::Element::new("path");
+                    pathxml.attributes.insert("id".to_owned(), format!("{}contour-{}", id_prefix, i));
+                    pathxml.attributes.insert("d".to_owned(), d);
+                    xmltree::XMLNode::Element(pathxml)
+                }).collect())
+            }
+        }
+    }
+
+    /// The glif's own contours as `<path>`s, and its components as `<use>`s of `<symbol>`s, which
+    /// are added to `symbols` the first time each base glyph is used. `expanding` is the glyphs
+    /// whose `<symbol>`s are being written, outermost first. A `<use>` of one of them would make
+    /// the document cyclic, and is left out.
+    fn paths_and_uses(&self, frame_pen: &SVGPathPen, resolver: &mut ComponentResolver, glif: &glifparser::Glif<()>, id_prefix: &str, symbols: &mut Vec<xmltree::XMLNode>, expanding: &mut Vec<String>) -> Result<Vec<xmltree::XMLNode>, PenError> {
+        let mut nodes = vec![];
+
+        if let Some(o) = glif.outline.as_ref() {
+            nodes.extend(self.outline_paths(frame_pen, o, id_prefix)?);
+        }
+
+        for component in glif.components.vec.iter() {
+            if expanding.contains(&component.base) {
+                eprintln!("Component {} of {} is its own base ({} → {}), left out", component.base, glif.name, expanding.join(" → "), component.base);
+                continue
+            }
+            let base = match resolver.base(&component.base) {
+                Some(base) => base.clone(),
+                None => continue,
+            };
+            let id = format!("glyph-{}", component.base);
+
+            let written = symbols.iter().any(|n|n.as_element().and_then(|e|e.attributes.get("id")) == Some(&id));
+            if !written {
+                let mut symbolxml = xmltree::Element::new("symbol");
+                symbolxml.attributes.insert("id".to_owned(), id.clone());
+                // Glyphs are drawn around the origin, don't clip them to the <use>'s viewport.
+                symbolxml.attributes.insert("overflow".to_owned(), "visible".to_owned());
+                let prefix = format!("{}-", id);
+                expanding.push(component.base.clone());
+                symbolxml.children = self.paths_and_uses(frame_pen, resolver, &base, &prefix, symbols, expanding)?;
+                expanding.pop();
+                symbols.push(xmltree::XMLNode::Element(symbolxml));
+            }
+
+            let mut usexml = xmltree::Element::new("use");
+            // SVG 2 `href`, and `xlink:href` for SVG 1.1 readers like older Inkscapes.
+            usexml.attributes.insert("href".to_owned(), format!("#{}", id));
+            usexml.attributes.insert("xlink:href".to_owned(), format!("#{}", id));
+            usexml.attributes.insert("transform".to_owned(), frame_pen.svg_matrix(&Affine::from_component(component)));
+            nodes.push(xmltree::XMLNode::Element(usexml));
+        }
+
+        Ok(nodes)
+    }
+
+    /// The SVG document as text, indented and newline-terminated.
+    pub fn to_svg(&self, glif: &glifparser::Glif<()>) -> Result<String, Glif2SvgError> {
+        write_element(&self.to_element(glif)?)
+    }
+}
+
+/// An SVG document as text, indented and newline-terminated.
+pub fn write_element(svgxml: &xmltree::Element) -> Result<String, Glif2SvgError> {
+    let emit_error = |message: String|Glif2SvgError::Emit { path: None, message };
+
+    let config = xmltree::EmitterConfig::new().perform_indent(true).indent_string("    ");
+    let mut outxml = Vec::<u8>::new();
+
+    svgxml.write_with_config(&mut outxml, config).map_err(|e|emit_error(e.to_string()))?;
+
+    outxml.push('\n' as u8);
+
+    Ok(stdstr::from_utf8(&outxml).map_err(|e|emit_error(e.to_string()))?.to_owned())
+}
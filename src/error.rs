@@ -0,0 +1,101 @@
+use crate::pen::PenError;
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Why a conversion failed. Each kind has its own process exit code, see [`Glif2SvgError::exit_code`].
+#[derive(Debug)]
+pub enum Glif2SvgError {
+    /// Bad command line, e.g. a UFO with no output directory.
+    Usage(String),
+    /// A file couldn't be read or written.
+    Io { path: Option<PathBuf>, source: io::Error },
+    /// A glif, SVG, or plist couldn't be understood.
+    Parse { path: Option<PathBuf>, message: String },
+    /// The font's metrics were asked for but couldn't be found.
+    Metrics { path: Option<PathBuf>, message: String },
+    /// The glyph couldn't be written as SVG (or glif).
+    Emit { path: Option<PathBuf>, message: String },
+    /// Some glyphs of a UFO failed, each already reported.
+    Batch { failed: usize, total: usize },
+}
+
+impl Glif2SvgError {
+    /// An error reading or writing `path`, which is a parse error if the data was invalid.
+    pub fn from_io(path: impl AsRef<Path>, e: io::Error) -> Self {
+        let path = Some(path.as_ref().to_owned());
+        match e.kind() {
+            io::ErrorKind::InvalidData => Glif2SvgError::Parse { path, message: e.to_string() },
+            _ => Glif2SvgError::Io { path, source: e },
+        }
+    }
+
+    /// The error about `path`, unless it already names a file.
+    pub fn at(mut self, file: impl AsRef<Path>) -> Self {
+        match &mut self {
+            Glif2SvgError::Io { path, .. } | Glif2SvgError::Parse { path, .. } |
+            Glif2SvgError::Metrics { path, .. } | Glif2SvgError::Emit { path, .. } => {
+                path.get_or_insert_with(||file.as_ref().to_owned());
+            }
+            Glif2SvgError::Usage(_) | Glif2SvgError::Batch { .. } => (),
+        }
+        self
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Glif2SvgError::Usage(_) => 1,
+            Glif2SvgError::Io { .. } => 2,
+            Glif2SvgError::Parse { .. } => 3,
+            Glif2SvgError::Metrics { .. } => 4,
+            Glif2SvgError::Emit { .. } => 5,
+            Glif2SvgError::Batch { .. } => 6,
+        }
+    }
+}
+
+impl fmt::Display for Glif2SvgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (path, message) = match self {
+            Glif2SvgError::Usage(message) => return write!(f, "{}", message),
+            Glif2SvgError::Batch { failed, total } => return write!(f, "{} of {} glyphs failed to convert", failed, total),
+            Glif2SvgError::Io { path, source } => (path, source.to_string()),
+            Glif2SvgError::Parse { path, message } => (path, format!("Failed to parse: {}", message)),
+            Glif2SvgError::Metrics { path, message } => (path, message.clone()),
+            Glif2SvgError::Emit { path, message } => (path, format!("Failed to write: {}", message)),
+        };
+        match path {
+            Some(path) => write!(f, "{}: {}", path.display(), message),
+            None => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for Glif2SvgError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Glif2SvgError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// An I/O error for data that couldn't be understood, which [`Glif2SvgError::from_io`] makes a
+/// parse error.
+pub(crate) fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+impl From<io::Error> for Glif2SvgError {
+    fn from(e: io::Error) -> Self {
+        Glif2SvgError::Io { path: None, source: e }
+    }
+}
+
+impl From<PenError> for Glif2SvgError {
+    fn from(e: PenError) -> Self {
+        Glif2SvgError::Emit { path: None, message: e.to_string() }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::error::invalid_data;
+use crate::ufo::{plist_error, plist_number};
+
+use plist;
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The parts of a UFO's `fontinfo.plist` that matter for framing glyphs and naming fonts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FontInfo {
+    pub family_name: Option<String>,
+    pub units_per_em: Option<f64>,
+    pub ascender: Option<f64>,
+    pub descender: Option<f64>,
+    pub x_height: Option<f64>,
+    pub cap_height: Option<f64>,
+    pub guidelines: Vec<FontGuideline>,
+}
+
+/// A font-wide `guidelines` entry, normalized so that a line given only by `y` has an angle of 0°
+/// and one given only by `x` an angle of 90°.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FontGuideline {
+    pub x: f64,
+    pub y: f64,
+    /// Counterclockwise, in degrees.
+    pub angle: f64,
+    pub name: Option<String>,
+    pub identifier: Option<String>,
+}
+
+impl FontGuideline {
+    fn from_dict(dict: &plist::Dictionary) -> Option<Self> {
+        let (x, y) = (number(dict, "x"), number(dict, "y"));
+        let angle = match (x, y) {
+            (None, None) => return None,
+            (Some(_), None) => 90.,
+            (None, Some(_)) => 0.,
+            (Some(_), Some(_)) => number(dict, "angle")?,
+        };
+        let string = |key: &str|dict.get(key).and_then(plist::Value::as_string).map(str::to_owned);
+        Some(FontGuideline {
+            x: x.unwrap_or(0.),
+            y: y.unwrap_or(0.),
+            angle,
+            name: string("name"),
+            identifier: string("identifier"),
+        })
+    }
+}
+
+fn number(dict: &plist::Dictionary, key: &str) -> Option<f64> {
+    dict.get(key).and_then(plist_number)
+}
+
+impl FontInfo {
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let dict = plist::Value::from_file(path).map_err(plist_error)?
+            .into_dictionary()
+            .ok_or_else(||invalid_data(format!("{} is not a dictionary", path.display())))?;
+
+        Ok(FontInfo {
+            family_name: dict.get("familyName").and_then(plist::Value::as_string).map(str::to_owned),
+            units_per_em: number(&dict, "unitsPerEm"),
+            ascender: number(&dict, "ascender"),
+            descender: number(&dict, "descender"),
+            x_height: number(&dict, "xHeight"),
+            cap_height: number(&dict, "capHeight"),
+            guidelines: dict.get("guidelines")
+                .and_then(plist::Value::as_array)
+                .map(|guidelines| {
+                    guidelines.iter()
+                        .filter_map(plist::Value::as_dictionary)
+                        .filter_map(FontGuideline::from_dict)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+
+    /// `fontinfo.plist` of the UFO a glif is in, i.e. `font.ufo/glyphs/A_.glif` → `font.ufo/fontinfo.plist`.
+    pub fn path_for_glif(glif_path: impl AsRef<Path>) -> Option<PathBuf> {
+        let ufo = glif_path.as_ref().parent()?.parent()?;
+        Some(ufo.join("fontinfo.plist")).filter(|p|p.is_file())
+    }
+
+    pub fn ascender_descender(&self) -> Option<(f64, f64)> {
+        Some((self.ascender?, self.descender?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    fn fontinfo(name: &str, dict: &str) -> FontInfo {
+        let path = std::env::temp_dir().join(format!("glif2svg-test-{}-{}.plist", name, std::process::id()));
+        fs::write(&path, format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0"><dict>{}</dict></plist>
+"#, dict)).unwrap();
+        FontInfo::from_file(&path).unwrap()
+    }
+
+    fn guideline(x: f64, y: f64, angle: f64) -> FontGuideline {
+        FontGuideline { x, y, angle, ..FontGuideline::default() }
+    }
+
+    #[test]
+    fn integer_and_real_values() {
+        let fontinfo = fontinfo("values", r#"
+            <key>familyName</key><string>Test Sans</string>
+            <key>unitsPerEm</key><integer>1000</integer>
+            <key>ascender</key><real>750.5</real>
+            <key>descender</key><integer>-250</integer>
+            <key>xHeight</key><string>500</string>"#);
+        assert_eq!(fontinfo.family_name.as_deref(), Some("Test Sans"));
+        assert_eq!(fontinfo.units_per_em, Some(1000.));
+        assert_eq!(fontinfo.ascender_descender(), Some((750.5, -250.)));
+        // Not a number, so not there
+        assert_eq!(fontinfo.x_height, None);
+        assert_eq!(fontinfo.cap_height, None);
+        assert!(fontinfo.guidelines.is_empty());
+    }
+
+    #[test]
+    fn guideline_angles() {
+        let fontinfo = fontinfo("guidelines", r#"
+            <key>guidelines</key>
+            <array>
+                <dict><key>x</key><integer>100</integer><key>name</key><string>stem</string></dict>
+                <dict><key>y</key><real>500.5</real><key>identifier</key><string>g1</string></dict>
+                <dict><key>x</key><integer>10</integer><key>y</key><integer>20</integer><key>angle</key><real>45.5</real></dict>
+                <dict><key>x</key><integer>10</integer><key>y</key><integer>20</integer><key>angle</key><integer>30</integer></dict>
+            </array>"#);
+        assert_eq!(fontinfo.guidelines, vec![
+            FontGuideline { name: Some("stem".to_owned()), ..guideline(100., 0., 90.) },
+            FontGuideline { identifier: Some("g1".to_owned()), ..guideline(0., 500.5, 0.) },
+            guideline(10., 20., 45.5),
+            guideline(10., 20., 30.),
+        ]);
+    }
+
+    #[test]
+    fn guidelines_left_out_without_angle_or_position() {
+        let fontinfo = fontinfo("bad-guidelines", r#"
+            <key>guidelines</key>
+            <array>
+                <dict><key>x</key><integer>10</integer><key>y</key><integer>20</integer></dict>
+                <dict><key>angle</key><integer>30</integer></dict>
+                <string>not a guideline</string>
+                <dict><key>y</key><integer>-100</integer></dict>
+            </array>"#);
+        assert_eq!(fontinfo.guidelines, vec![guideline(0., -100., 0.)]);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::fontinfo::FontInfo;
+use crate::pen::SVGPathPen;
+
+use glifparser::{Anchor, Guideline};
+use xmltree;
+
+/// Radius of anchor markers, in font units.
+const MARKER_RADIUS: f32 = 5.;
+
+/// Rounds away `-0.00`, which trigonometry is fond of.
+fn unsigned_zero(f: f64) -> f64 {
+    if f.abs() < 0.005 { 0. } else { f }
+}
+
+/// A `sodipodi:guide`. Its position is from the bottom left of the page (see
+/// [`SVGPathPen::page_position`]) and its orientation is the normal of the guide line.
+pub fn guide(id: &str, label: Option<&str>, position: (f64, f64), orientation: (f64, f64)) -> xmltree::Element {
+    let mut guidexml = xmltree::Element::new("sodipodi:guide");
+    guidexml.attributes.insert("id".to_owned(), id.to_owned());
+    guidexml.attributes.insert("position".to_owned(), format!("{:.2},{:.2}", position.0, position.1));
+    guidexml.attributes.insert("orientation".to_owned(), format!("{:.2},{:.2}", unsigned_zero(orientation.0), unsigned_zero(orientation.1)));
+    if let Some(label) = label {
+        guidexml.attributes.insert("inkscape:label".to_owned(), label.to_owned());
+    }
+    guidexml
+}
+
+fn anchor_name(anchor: &Anchor<()>, i: usize) -> String {
+    anchor.class.clone().unwrap_or_else(||format!("anchor{}", i))
+}
+
+/// A horizontal and a vertical guide crossing at each anchor. `frame_pen` is the pen the outline
+/// was transformed with, `svg` the one the page is sized by.
+pub fn anchor_guides(svg: &SVGPathPen, frame_pen: &SVGPathPen, anchors: &[Anchor<()>]) -> Vec<xmltree::Element> {
+    anchors.iter().enumerate().flat_map(|(i, anchor)| {
+        let name = anchor_name(anchor, i);
+        let position = svg.page_position(frame_pen.transform_x(anchor.x), frame_pen.transform_y(anchor.y));
+        [
+            guide(&format!("anchor-{}-h", name), Some(&name), position, (0., 1.)),
+            guide(&format!("anchor-{}-v", name), Some(&name), position, (1., 0.)),
+        ]
+    }).collect()
+}
+
+/// A guide for each of the glif's `<guideline>`s. UFO angles are counterclockwise degrees from
+/// the x-axis, and guide positions are y-up too (see [`SVGPathPen::page_position`]), so only the
+/// position goes through the y-flip.
+pub fn guideline_guides(svg: &SVGPathPen, frame_pen: &SVGPathPen, guidelines: &[Guideline<()>]) -> Vec<xmltree::Element> {
+    guidelines.iter().enumerate().map(|(i, guideline)| {
+        let id = guideline.identifier.clone()
+            .or_else(||guideline.name.as_ref().map(|name|format!("guideline-{}", name)))
+            .unwrap_or_else(||format!("guideline{}", i));
+        let position = svg.page_position(frame_pen.transform_x(guideline.at.x), frame_pen.transform_y(guideline.at.y));
+        let angle = (f32::from(guideline.angle) as f64).to_radians();
+        let orientation = (-angle.sin(), angle.cos());
+        guide(&id, guideline.name.as_deref(), position, orientation)
+    }).collect()
+}
+
+/// Guides for the font's vertical metrics and each of its fontinfo.plist guidelines.
+pub fn metric_guides(svg: &SVGPathPen, frame_pen: &SVGPathPen, fontinfo: &FontInfo) -> Vec<xmltree::Element> {
+    let metrics = [
+        ("ascender", fontinfo.ascender),
+        ("descender", fontinfo.descender),
+        ("xHeight", fontinfo.x_height),
+        ("capHeight", fontinfo.cap_height),
+    ];
+    let mut guides: Vec<_> = metrics.iter().filter_map(|(name, y)| {
+        let position = svg.page_position(frame_pen.transform_x(0.), frame_pen.transform_y((*y)? as f32));
+        Some(guide(name, Some(*name), position, (0., 1.)))
+    }).collect();
+
+    guides.extend(fontinfo.guidelines.iter().enumerate().map(|(i, guideline)| {
+        let id = guideline.identifier.clone()
+            .or_else(||guideline.name.as_ref().map(|name|format!("font-guideline-{}", name)))
+            .unwrap_or_else(||format!("font-guideline{}", i));
+        let position = svg.page_position(frame_pen.transform_x(guideline.x as f32), frame_pen.transform_y(guideline.y as f32));
+        let angle = guideline.angle.to_radians();
+        guide(&id, guideline.name.as_deref(), position, (-angle.sin(), angle.cos()))
+    }));
+
+    guides
+}
+
+/// An Inkscape layer with a small labelled circle on each anchor.
+pub fn anchor_markers(frame_pen: &SVGPathPen, anchors: &[Anchor<()>]) -> xmltree::Element {
+    let mut layerxml = xmltree::Element::new("g");
+    layerxml.attributes.insert("id".to_owned(), "anchors".to_owned());
+    layerxml.attributes.insert("inkscape:groupmode".to_owned(), "layer".to_owned());
+    layerxml.attributes.insert("inkscape:label".to_owned(), "Anchors".to_owned());
+
+    for (i, anchor) in anchors.iter().enumerate() {
+        let name = anchor_name(anchor, i);
+        let mut circlexml = xmltree::Element::new("circle");
+        circlexml.attributes.insert("id".to_owned(), format!("anchor-{}", name));
+        circlexml.attributes.insert("inkscape:label".to_owned(), name.clone());
+        circlexml.attributes.insert("cx".to_owned(), frame_pen.p(frame_pen.transform_x(anchor.x)).to_string());
+        circlexml.attributes.insert("cy".to_owned(), frame_pen.p(frame_pen.transform_y(anchor.y)).to_string());
+        circlexml.attributes.insert("r".to_owned(), frame_pen.p(MARKER_RADIUS).to_string());
+        circlexml.attributes.insert("fill".to_owned(), "#ff0000".to_owned());
+        let mut titlexml = xmltree::Element::new("title");
+        titlexml.children.push(xmltree::XMLNode::Text(name));
+        circlexml.children.push(xmltree::XMLNode::Element(titlexml));
+        layerxml.children.push(xmltree::XMLNode::Element(circlexml));
+    }
+
+    layerxml
+}
@@ -0,0 +1,26 @@
+///! glif2svg in Rust
+///! (c) 2021–2022 Fredrick R. Brennan and MFEK authors. See LICENSE.
+
+#[macro_use] extern crate derivative; // for better #[derive(…)]
+
+mod svg_boilerplate;
+pub mod pen;
+pub mod convert;
+pub mod ufo;
+pub mod fontinfo;
+pub mod components;
+pub mod guides;
+pub mod svg_path;
+pub mod svg2glif;
+pub mod batch;
+pub mod render;
+pub mod svgfont;
+pub mod otsvg;
+pub mod specimen;
+pub mod error;
+
+pub use error::Glif2SvgError;
+pub use pen::{PenError, SVGPathPen};
+pub use convert::{AnchorMode, ComponentMode, ContourMode, Converter, Format, MetricsSource, Options, OutlineMode, RasterSize, ViewBoxMode};
+pub use fontinfo::{FontGuideline, FontInfo};
+pub use ufo::Ufo;
@@ -1,160 +1,194 @@
 ///! glif2svg in Rust
 ///! (c) 2021–2022 Fredrick R. Brennan and MFEK authors. See LICENSE.
 
-#[macro_use] extern crate derivative; // for better #[derive(…)]
-
-mod svg_boilerplate;
-use svg_boilerplate::*;
+use glif2svg::{AnchorMode, ComponentMode, ContourMode, Converter, Format, Glif2SvgError, MetricsSource, Options, OutlineMode, RasterSize, Ufo, ViewBoxMode};
+use glif2svg::batch;
+use glif2svg::convert::write_element;
+use glif2svg::{otsvg, specimen, svgfont};
+use glif2svg::svg2glif::SvgReader;
 
 use glifparser;
-use glifparser::IntegerOrFloat;
-use glifparser::outline::skia::SkiaPointTransforms;
-use glifparser::outline::skia::ToSkiaPaths as _;
-use clap::{self, App, AppSettings, Arg};
-use skia_safe::{Point, path::Verb};
-use skia_safe::path::Iter as SkIter;
-use mfek_ipc::{self, IPCInfo};
+use clap::{self, App, AppSettings, Arg, ArgMatches, SubCommand};
 use xmltree;
 
-use std::{fs, str as stdstr};
-
-type XmlTreeAttribute = (String, String);
-
-#[derive(Debug, Derivative)]
-#[derivative(Default(new="true"))]
-struct SVGPathPen {
-    path: String,
-    minx: f64,
-    maxx: f64,
-    miny: f64,
-    maxy: f64,
-    #[derivative(Default(value="4"))]
-    precision: u8,
-    no_viewbox: bool
-}
-
-fn consider_min_max(svg: &mut SVGPathPen, points: &[Point]) {
-    for p in points {
-        if (svg.minx as f32) > p.x { svg.minx = p.x.into(); }
-        if (svg.maxx as f32) < p.x { svg.maxx = p.x.into(); }
-        if (svg.miny as f32) > p.y { svg.miny = p.y.into(); }
-        if (svg.maxy as f32) < p.y { svg.maxy = p.y.into(); }
+use std::fs;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+
+/// Writes `out` to `output`, or to stdout if it's not given or `-`.
+fn write_output(output: Option<&str>, out: &str) -> Result<(), Glif2SvgError> {
+    match output {
+        Some(outfile) if outfile != "-" => fs::write(outfile, out).map_err(|e|Glif2SvgError::from_io(outfile, e)),
+        _ => {
+            println!("{}", out);
+            Ok(())
+        }
     }
 }
 
-impl SVGPathPen {
-    fn extend_path(&mut self, path: &str) {
-        self.path.push_str(path);
-    }
-
-    #[allow(non_snake_case)]
-    fn viewBox(&self) -> (f64, f64, f64, f64) {
-        return (self.minx, self.miny, self.minx.abs() + self.maxx, self.miny.abs() + self.maxy)
-    }
-
-    fn width(&self) -> f64 {
-        self.viewBox().2
-    }
-
-    fn height(&self) -> f64 {
-        self.viewBox().3
-    }
-
-    fn p(&self, size: impl Into<IntegerOrFloat>) -> IntegerOrFloat {
-        let f: f32 = f32::from(size.into());
-        let precision = (10.0_f32).powf(self.precision as f32);
-        IntegerOrFloat::from(f32::trunc(f * precision) / precision)
-    }
-
-    fn size_attr_impl(&self, name: &'static str, size: impl Into<IntegerOrFloat>) -> XmlTreeAttribute {
-        let name = name.to_string();
-        let size = format!("{}px", self.p(size.into()));
-        (name, size.into())
-    }
-
-    fn px_size_attrs(&self) -> [XmlTreeAttribute; 2] {
-        [
-            self.size_attr_impl("width", self.width()),
-            self.size_attr_impl("height", self.height()),
-        ]
+/// Like [`write_output`], for binary formats.
+fn write_binary_output(output: Option<&str>, out: &[u8]) -> Result<(), Glif2SvgError> {
+    match output {
+        Some(outfile) if outfile != "-" => fs::write(outfile, out).map_err(|e|Glif2SvgError::from_io(outfile, e)),
+        _ => io::stdout().write_all(out).map_err(Glif2SvgError::from),
     }
+}
 
-    #[allow(non_snake_case)]
-    fn viewBox_str(&self) -> String {
-        let (x, y, dx, dy) = self.viewBox();
-        format!("{} {} {} {}", self.p(x), self.p(y), self.p(dx), self.p(dy))
-    }
+fn svg2glif(matches: &ArgMatches) -> Result<(), Glif2SvgError> {
+    let input = matches.value_of("input").unwrap();
+    let output = matches.value_of("output");
 
-    fn transform_x(&self, x: f32) -> f32 {
-        x
+    let svgfile = fs::File::open(input).map_err(|e|Glif2SvgError::from_io(input, e))?;
+    let svgxml = xmltree::Element::parse(svgfile)
+        .map_err(|e|Glif2SvgError::Parse { path: Some(PathBuf::from(input)), message: e.to_string() })?;
+    let mut glif = SvgReader::new(&svgxml).to_glif(matches.value_of("name")).map_err(|e|Glif2SvgError::from_io(input, e))?;
+    if glif.name.is_empty() {
+        glif.name = Path::new(input).file_stem().unwrap_or_default().to_string_lossy().into_owned();
     }
+    let glifxml = glifparser::glif::write(&glif)
+        .map_err(|e|Glif2SvgError::Emit { path: Some(PathBuf::from(input)), message: format!("{:?}", e) })?;
 
-    #[allow(non_snake_case)]
-    fn transform_y_viewBox(&self, y: f32) -> f32 {
-        (-y) + self.maxy as f32 + self.miny as f32
-    }
-
-    fn transform_y_wh(&self, y: f32) -> f32 {
-        (-y) + self.miny as f32
-    }
+    write_output(output, &glifxml)
+}
 
-    fn transform_y(&self, y: f32) -> f32 {
-        if self.no_viewbox {
-            self.transform_y_wh(y)
-        } else {
-            self.transform_y_viewBox(y)
-        }
-    }
+fn svgfont(matches: &ArgMatches, mut options: Options) -> Result<(), Glif2SvgError> {
+    let input = matches.value_of("input").unwrap();
+    let ufo = Ufo::open(input).map_err(|e|Glif2SvgError::from_io(input, e))?;
+    options.ufo = Some(ufo.clone());
 
-    fn move_to(&mut self, pt: Point) {
-        consider_min_max(self, &[pt]);
-        self.extend_path(&format!("M {} {}", self.p(pt.x), self.p(pt.y)));
-    }
+    let svgxml = svgfont::svg_font(&Converter::new(options), &ufo)?;
+    write_output(matches.value_of("output"), &write_element(&svgxml)?)
+}
 
-    fn line_to(&mut self, pt: Point) {
-        consider_min_max(self, &[pt]);
-        self.extend_path(&format!("L {} {}", self.p(pt.x), self.p(pt.y)));
-    }
+fn otsvg(matches: &ArgMatches, mut options: Options) -> Result<(), Glif2SvgError> {
+    let input = matches.value_of("input").unwrap();
+    let output = matches.value_of("output");
+    let ufo = Ufo::open(input).map_err(|e|Glif2SvgError::from_io(input, e))?;
+    options.ufo = Some(ufo.clone());
 
-    fn curve_to(&mut self, pt: &[Point]) {
-        consider_min_max(self, pt);
-        self.extend_path(&format!("C {} {} {} {} {} {}", self.p(pt[1].x), self.p(pt[1].y), self.p(pt[2].x), self.p(pt[2].y), self.p(pt[3].x), self.p(pt[3].y)));
+    let order = otsvg::glyph_order(&ufo, matches.value_of("glyph_order").map(Path::new))?;
+    let glyphs = otsvg::glyph_elements(&Converter::new(options), &ufo, &order)?;
+    if matches.is_present("bundle") {
+        return write_output(output, &write_element(&otsvg::bundle(glyphs))?)
     }
-
-    fn qcurve_to(&mut self, pt: &[Point]) {
-        consider_min_max(self, pt);
-        self.extend_path(&format!("Q {} {} {} {}", self.p(pt[1].x), self.p(pt[1].y), self.p(pt[2].x), self.p(pt[2].y)));
+    match output {
+        Some(outdir) if outdir != "-" => otsvg::write_documents(glyphs, Path::new(outdir)),
+        _ => Err(Glif2SvgError::Usage("An output directory is required for a document per glyph, or --bundle".to_owned())),
     }
+}
 
-    fn close_path(&mut self) {
-        self.extend_path("Z");
-    }
+fn specimen(matches: &ArgMatches, mut options: Options, no_metrics: bool) -> Result<(), Glif2SvgError> {
+    let input = matches.value_of("input").unwrap();
+    let ufo = Ufo::open(input).map_err(|e|Glif2SvgError::from_io(input, e))?;
+    options.metrics = match ufo.fontinfo_path() {
+        Some(fi) if !no_metrics => MetricsSource::Fontinfo(fi),
+        _ => MetricsSource::Bounds,
+    };
+    options.ufo = Some(ufo.clone());
+
+    let names = match matches.value_of("glyphs") {
+        Some(glyphs) => glyphs.split_whitespace().map(str::to_owned).collect(),
+        None => specimen::glyph_names(&ufo, matches.value_of("text").unwrap())?,
+    };
+    let kerning = if matches.is_present("kern") { Some(specimen::Kerning::from_ufo(&ufo)?) } else { None };
+
+    let svgxml = specimen::specimen(&Converter::new(options), &ufo, &names, kerning.as_ref())?;
+    write_output(matches.value_of("output"), &write_element(&svgxml)?)
+}
 
-    fn apply_outline(&mut self, outline: &glifparser::Outline<()>) {
-        let skia_paths = outline.to_skia_paths(Some(SkiaPointTransforms { calc_x: &|x|self.transform_x(x), calc_y: &|y|self.transform_y(y) }));
-        for path in skia_paths.open.iter().chain(skia_paths.closed.iter()) {
-            let iter = SkIter::new(&path, false);
-            for (verb, pts) in iter {
-                match verb {
-                    Verb::Move => self.move_to(pts[0]),
-                    Verb::Line => self.line_to(pts[1]),
-                    Verb::Quad => self.qcurve_to(&pts),
-                    Verb::Cubic => self.curve_to(&pts),
-                    Verb::Close => self.close_path(),
-                    _ => {unimplemented!()}
-                }
-            }
-        }
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}", e);
+        std::process::exit(e.exit_code());
     }
 }
 
-fn main() {
+fn run() -> Result<(), Glif2SvgError> {
     let matches = App::new("glif2svg")
         .setting(AppSettings::ArgRequiredElseHelp)
         .setting(AppSettings::DeriveDisplayOrder)
         .version(env!("CARGO_PKG_VERSION"))
         .author("Fredrick R. Brennan <copypasteⒶkittens⊙ph>; MFEK Authors")
-        .about("Convert between glif to SVG")
+        .about("Convert between glif and SVG")
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .subcommand(SubCommand::with_name("svg2glif")
+            .setting(AppSettings::ArgRequiredElseHelp)
+            .about("Convert SVG to glif, undoing glif2svg's y-flip")
+            .arg(Arg::with_name("input")
+                .index(1)
+                .required(true)
+                .help("The path to the input SVG."))
+            .arg(Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .help("The path to the output glif. If not provided, or `-`, stdout."))
+            .arg(Arg::with_name("name")
+                .short("n")
+                .long("name")
+                .takes_value(true)
+                .help("Glyph name, if not the embedded glif's or the input's file name without extension")))
+        .subcommand(SubCommand::with_name("svgfont")
+            .setting(AppSettings::ArgRequiredElseHelp)
+            .about("Convert a whole UFO to one SVG font, with its kerning")
+            .arg(Arg::with_name("input")
+                .index(1)
+                .required(true)
+                .help("The path to the input UFO."))
+            .arg(Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .help("The path to the output SVG. If not provided, or `-`, stdout.")))
+        .subcommand(SubCommand::with_name("otsvg")
+            .setting(AppSettings::ArgRequiredElseHelp)
+            .about("Convert a whole UFO to glyph documents for an OpenType SVG table")
+            .arg(Arg::with_name("input")
+                .index(1)
+                .required(true)
+                .help("The path to the input UFO."))
+            .arg(Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .help("The directory to write glyphN.svg documents to, or with --bundle the output SVG (stdout if not provided, or `-`)."))
+            .arg(Arg::with_name("glyph_order")
+                .short("g")
+                .long("glyph-order")
+                .takes_value(true)
+                .help("A file of glyph names, one per line, in glyph ID order. If not provided, the UFO's public.glyphOrder"))
+            .arg(Arg::with_name("bundle")
+                .short("b")
+                .long("bundle")
+                .help("Write all glyphs into one document")))
+        .subcommand(SubCommand::with_name("specimen")
+            .setting(AppSettings::ArgRequiredElseHelp)
+            .about("Set a line of a UFO's glyphs by their advance widths, as one SVG")
+            .arg(Arg::with_name("input")
+                .index(1)
+                .required(true)
+                .help("The path to the input UFO."))
+            .arg(Arg::with_name("text")
+                .short("t")
+                .long("text")
+                .takes_value(true)
+                .required_unless("glyphs")
+                .help("Text to set, by the glyphs' unicodes"))
+            .arg(Arg::with_name("glyphs")
+                .short("g")
+                .long("glyphs")
+                .takes_value(true)
+                .conflicts_with("text")
+                .help("Glyph names to set, separated by spaces"))
+            .arg(Arg::with_name("kern")
+                .short("k")
+                .long("kern")
+                .help("Apply kerning.plist between glyphs"))
+            .arg(Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .help("The path to the output SVG. If not provided, or `-`, stdout.")))
         .arg(Arg::with_name("input_file")
             .short("in")
             .long("input")
@@ -163,7 +197,7 @@ fn main() {
             .hidden(true))
         .arg(Arg::with_name("input")
             .index(1)
-            .help("The path to the input file.")
+            .help("The path to the input file, or a UFO/glyphs directory to convert every glyph of.")
             .conflicts_with("input_file")
             .required_unless("input_file"))
         .arg(Arg::with_name("output_file")
@@ -172,7 +206,7 @@ fn main() {
             .takes_value(true)
             .conflicts_with("output")
             .display_order(1)
-            .help("The path to the output file. If not provided, or `-`, stdout.\n\n\n"))
+            .help("The path to the output file. If not provided, or `-`, stdout. If input is a UFO, the output directory.\n\n\n"))
         .arg(Arg::with_name("output")
             .index(2)
             .hidden(true))
@@ -184,106 +218,190 @@ fn main() {
             .short("M")
             .long("no-metrics")
             .help("Don't consider glif's height/width when writing SVG, use minx/maxx/miny/maxy"))
+        .arg(Arg::with_name("use_components")
+            .short("U")
+            .long("use-components")
+            .help("Write components as <use>s of base glyph <symbol>s instead of flattening them"))
+        .arg(Arg::with_name("native")
+            .short("N")
+            .long("native")
+            .help("Write path data straight from the glif's points, keeping quadratic contours quadratic"))
+        .arg(Arg::with_name("optimize")
+            .short("O")
+            .long("optimize")
+            .help("Write the shortest path data: relative coordinates where shorter, H/V/S/T shorthands, fewer separators"))
+        .arg(Arg::with_name("split_contours")
+            .short("S")
+            .long("split-contours")
+            .help("Write each contour as its own <path id=\"contour-…\">"))
+        .arg(Arg::with_name("open_stroke")
+            .long("open-stroke")
+            .takes_value(true)
+            .default_value("black")
+            .help("Stroke color of open contours, which aren't filled"))
+        .arg(Arg::with_name("open_stroke_width")
+            .long("open-stroke-width")
+            .takes_value(true)
+            .default_value("1")
+            .validator(|w|Ok(w.parse::<f32>().map(|_|()).map_err(|_|String::from("Stroke width must be a number"))?))
+            .help("Stroke width of open contours"))
+        .arg(Arg::with_name("anchors")
+            .short("A")
+            .long("anchors")
+            .takes_value(true)
+            .possible_values(&["guides", "markers"])
+            .help("Write the glif's anchors as pairs of Inkscape guides, or as circles in an \"Anchors\" layer"))
+        .arg(Arg::with_name("round_trip")
+            .short("R")
+            .long("round-trip")
+            .help("Embed the glif, so that svg2glif can give it back exactly if its paths weren't edited"))
         .arg(Arg::with_name("fontinfo")
             .short("F")
             .long("fontinfo")
             .takes_value(true)
             .help("fontinfo file (for metrics, should point to fontinfo.plist path if you are using an unparented glif, a glif not in a parent UFO font)\n\n"))
+        .arg(Arg::with_name("no_ipc")
+            .short("I")
+            .long("no-ipc")
+            .help("Don't ask MFEKmetadata for metrics if fontinfo.plist can't be read"))
         .arg(Arg::with_name("precision")
             .short("p")
             .long("precision")
             .takes_value(true)
-            .default_value("6")
             .validator(|f|Ok(f.parse::<u8>().map(|_|()).map_err(|_|String::from("Precision must be 0…255"))?))
-            .help("Float precision"))
+            .help("Decimal places of coordinates, rounded half to even [default: 4]"))
+        .arg(Arg::with_name("integers")
+            .long("integers")
+            .conflicts_with("precision")
+            .help("Round coordinates to integers, same as -p 0"))
+        .arg(Arg::with_name("format")
+            .short("f")
+            .long("format")
+            .takes_value(true)
+            .possible_values(&["svg", "png", "pdf"])
+            .default_value("svg")
+            .help("Output format, PNG or PDF drawn by skia framed as the SVG would be. A UFO to PDF is one file, a page per glyph"))
+        .arg(Arg::with_name("dpi")
+            .long("dpi")
+            .takes_value(true)
+            .validator(|d|match d.parse::<f32>() {
+                Ok(d) if d.is_finite() && d > 0. => Ok(()),
+                _ => Err(String::from("DPI must be a positive number")),
+            })
+            .help("Resolution of PNGs, a font unit being a pixel at 96 [default: 96]"))
+        .arg(Arg::with_name("pixel_height")
+            .long("pixel-height")
+            .takes_value(true)
+            .conflicts_with("dpi")
+            .validator(|h|match h.parse::<u32>() {
+                Ok(h) if h > 0 => Ok(()),
+                _ => Err(String::from("Pixel height must be a positive whole number")),
+            })
+            .help("Height of PNGs in pixels, instead of --dpi"))
+        .arg(Arg::with_name("jobs")
+            .short("j")
+            .long("jobs")
+            .takes_value(true)
+            .default_value("0")
+            .validator(|j|Ok(j.parse::<usize>().map(|_|()).map_err(|_|String::from("Jobs must be a number of threads"))?))
+            .help("Threads to convert a UFO with, 0 for one per CPU core"))
         .get_matches();
 
-    let input = matches.value_of("input").unwrap_or_else(||matches.value_of("input_file").unwrap());
-    let output = matches.value_of("output").or_else(||matches.value_of("output_file"));
+    if let Some(matches) = matches.subcommand_matches("svg2glif") {
+        return svg2glif(matches)
+    }
+
     let no_viewbox = matches.is_present("no_viewbox");
     let no_metrics = matches.is_present("no_metrics");
     let fontinfo_o = matches.value_of("fontinfo");
+    let no_ipc = matches.is_present("no_ipc");
+    let jobs = matches.value_of("jobs").unwrap().parse::<usize>().unwrap();
 
-    let glif: glifparser::Glif<()> = glifparser::glif::read_from_filename(matches.value_of("input").unwrap()).unwrap();
-
-    let mut svg = SVGPathPen::new();
-    svg.precision = matches.value_of("precision").unwrap().parse::<u8>().unwrap();
-    svg.no_viewbox = no_viewbox;
-
-    if let (Ok(..), true) = (mfek_ipc::module::available("metadata".into(), "0.0.2-beta1"), !no_metrics) {
-        let ipc_info = if let Some(fi) = fontinfo_o {
-            IPCInfo::from_fontinfo_path("glif2svg".to_string(), &fi)
-        } else {
-            IPCInfo::from_glif_path("glif2svg".to_string(), &input)
-        };
-
-        if let Ok((ascender, descender)) = mfek_ipc::helpers::metadata::ascender_descender(&ipc_info) {
-            svg.maxy = ascender as f64;
-            svg.miny = descender as f64;
-        } else {
-            eprintln!("Failed to set metrics of SVG from glif font!");
-            if let Some(ref o) = glif.outline.as_ref() {
-                svg.apply_outline(o);
-            }
-        }
-    } else {
-        eprintln!("MFEKmetadata REQUIRED for sane UFO metrics into SVG");
+    let mut options = Options::new();
+    if let Some(precision) = matches.value_of("precision") {
+        options.precision = precision.parse::<u8>().unwrap();
     }
-
-    if !no_metrics {
-        svg.minx = 0.;
-        svg.maxx = glif.width.unwrap_or(0) as f64;
+    if matches.is_present("integers") {
+        options.precision = 0;
     }
-
-    let mut svgxml = xmltree::Element::new("svg");
-    let mut namespace = xmltree::Namespace::empty();
-    for (k, v) in XMLNS.into_iter() {
-        namespace.put(*k, *v);
+    options.viewbox = if no_viewbox { ViewBoxMode::WidthHeight } else { ViewBoxMode::ViewBox };
+    options.ipc_fallback = !no_ipc;
+    options.anchors = match matches.value_of("anchors") {
+        Some("guides") => AnchorMode::Guides,
+        Some("markers") => AnchorMode::Markers,
+        _ => AnchorMode::Ignore,
+    };
+    options.outline = if matches.is_present("native") { OutlineMode::Native } else { OutlineMode::Skia };
+    options.optimize_paths = matches.is_present("optimize");
+    options.contours = if matches.is_present("split_contours") { ContourMode::Split } else { ContourMode::Joined };
+    options.open_stroke = matches.value_of("open_stroke").unwrap().to_owned();
+    options.open_stroke_width = matches.value_of("open_stroke_width").unwrap().parse::<f32>().unwrap();
+    options.round_trip = matches.is_present("round_trip");
+    options.components = if matches.is_present("use_components") { ComponentMode::Use } else { ComponentMode::Flatten };
+    options.format = match matches.value_of("format") {
+        Some("png") => Format::Png,
+        Some("pdf") => Format::Pdf,
+        _ => Format::Svg,
+    };
+    if let Some(dpi) = matches.value_of("dpi") {
+        options.raster_size = RasterSize::Dpi(dpi.parse::<f32>().unwrap());
     }
-    svgxml.namespaces = Some(namespace);
-    svgxml.attributes.insert("version".to_owned(), "1.1".to_owned());
-    if no_viewbox {
-        for (k, v) in svg.px_size_attrs() {
-            svgxml.attributes.insert(k, v);
-        }
-    } else {
-        svgxml.attributes.insert("viewBox".to_owned(), svg.viewBox_str());
+    if let Some(height) = matches.value_of("pixel_height") {
+        options.raster_size = RasterSize::Height(height.parse::<u32>().unwrap());
     }
 
-    let mut sodipodixml = xmltree::Element::new(NAMEDVIEW_IDENT);
-    sodipodixml.attributes = NAMEDVIEW.into_iter().map(|(k, v)|((*k).to_owned(), (*v).to_owned())).collect();
-    let mut xygridxml = xmltree::Element::new(XYGRID_IDENT);
-    xygridxml.attributes = XYGRID.into_iter().map(|(k, v)|((*k).to_owned(), (*v).to_owned())).collect();
-    let mut guidexml = xmltree::Element::new("sodipodi:guide");
-    guidexml.attributes.insert("id".to_owned(), "baseline".to_owned());
-    guidexml.attributes.insert("position".to_owned(), format!("{:.2},{:.2}", 0.0, svg.miny.abs()));
-    guidexml.attributes.insert("orientation".to_owned(), "0.00,1.00".to_owned());
-    sodipodixml.children = vec![xmltree::XMLNode::Element(xygridxml), xmltree::XMLNode::Element(guidexml)];
-    svgxml.children.push(xmltree::XMLNode::Element(sodipodixml));
-
-    if let Some(ref o) = glif.outline.as_ref() {
-        svg.apply_outline(o);
+    if let Some(matches) = matches.subcommand_matches("svgfont") {
+        return svgfont(matches, options)
+    }
+    if let Some(matches) = matches.subcommand_matches("otsvg") {
+        return otsvg(matches, options)
+    }
+    if let Some(matches) = matches.subcommand_matches("specimen") {
+        return specimen(matches, options, no_metrics)
     }
 
-    let mut gxml = xmltree::Element::new("g");
-    gxml.attributes.insert("id".to_owned(), "glyph".to_owned());
-    let mut pathxml = xmltree::Element::new("path");
-    pathxml.attributes.insert("d".to_owned(), svg.path);
-    gxml.children = vec![xmltree::XMLNode::Element(pathxml)];
-    svgxml.children.push(xmltree::XMLNode::Element(gxml));
-
-    let config = xmltree::EmitterConfig::new().perform_indent(true).indent_string("    ");
-    let mut outxml = Vec::<u8>::new();
+    let input = matches.value_of("input").unwrap_or_else(||matches.value_of("input_file").unwrap());
+    let output = matches.value_of("output").or_else(||matches.value_of("output_file"));
 
-    svgxml.write_with_config(&mut outxml, config).unwrap();
+    if Path::new(input).is_dir() {
+        let outdir = match output {
+            Some(o) if o != "-" => o,
+            _ if options.format == Format::Pdf => return Err(Glif2SvgError::Usage("An output file is required to convert a UFO to PDF".to_owned())),
+            _ => return Err(Glif2SvgError::Usage("An output directory is required to convert a UFO".to_owned())),
+        };
+        let ufo = Ufo::open(input).map_err(|e|Glif2SvgError::from_io(input, e))?;
+        options.metrics = if no_metrics {
+            MetricsSource::Bounds
+        } else if let Some(fi) = fontinfo_o.map(PathBuf::from).or_else(||ufo.fontinfo_path()) {
+            MetricsSource::Fontinfo(fi)
+        } else if let Some((_, filename)) = ufo.contents.first() {
+            MetricsSource::GlifPath(ufo.glif_path(filename))
+        } else {
+            MetricsSource::Bounds
+        };
+        options.ufo = Some(ufo.clone());
+        let mut converter = Converter::new(options);
+        converter.resolve_metrics()?;
+        if converter.options.format == Format::Pdf {
+            return batch::convert_ufo_pdf(&converter, &ufo, Path::new(outdir), jobs)
+        }
+        return batch::convert_ufo(&converter, &ufo, Path::new(outdir), jobs)
+    }
 
-    outxml.push('\n' as u8);
+    let glif = batch::read_glif(Path::new(input))?;
 
-    if let Some(outfile) = output {
-        if outfile != "-" {
-            fs::write(outfile, &outxml).unwrap();
-            return
-        }   
+    options.metrics = if no_metrics {
+        MetricsSource::Bounds
+    } else if let Some(fi) = fontinfo_o {
+        MetricsSource::Fontinfo(PathBuf::from(fi))
+    } else {
+        MetricsSource::GlifPath(PathBuf::from(input))
+    };
+    options.ufo = Ufo::containing(input);
+
+    let converter = Converter::new(options);
+    match converter.options.format {
+        Format::Svg => write_output(output, &converter.to_svg(&glif).map_err(|e|e.at(input))?),
+        _ => write_binary_output(output, &converter.to_bytes(&glif).map_err(|e|e.at(input))?),
     }
-    println!("{}", stdstr::from_utf8(&outxml).unwrap());
 }
@@ -0,0 +1,77 @@
+//! Glyph documents for an OpenType `SVG ` table.
+//!
+//! OT-SVG glyphs are drawn in font units with the origin on the baseline, y pointing down as usual
+//! in SVG, so a glyph's y is the glif's negated; and documents have no viewBox. Each glyph is the
+//! element with `id="glyphN"`, N being its glyph ID, i.e. its index in the font's glyph order.
+
+use crate::batch;
+use crate::convert::{write_element, Converter};
+use crate::error::Glif2SvgError;
+use crate::svg_boilerplate::svg_root;
+use crate::ufo::Ufo;
+
+use xmltree;
+
+use std::fs;
+use std::path::Path;
+
+/// The glyph order from a file of one glyph name per line (`#` starting comments), else the UFO's
+/// `public.glyphOrder`, else its `contents.plist` order.
+pub fn glyph_order(ufo: &Ufo, order_file: Option<&Path>) -> Result<Vec<String>, Glif2SvgError> {
+    if let Some(order_file) = order_file {
+        let order = fs::read_to_string(order_file).map_err(|e|Glif2SvgError::from_io(order_file, e))?;
+        return Ok(order.lines()
+            .map(|line|line.split('#').next().unwrap_or_default().trim())
+            .filter(|name|!name.is_empty())
+            .map(str::to_owned)
+            .collect())
+    }
+
+    let order = ufo.glyph_order().map_err(|e|Glif2SvgError::from_io(ufo.font_file("lib.plist").unwrap_or_default(), e))?;
+    Ok(order.unwrap_or_else(||ufo.contents.iter().map(|(name, _)|name.clone()).collect()))
+}
+
+fn document(glyphs: Vec<xmltree::XMLNode>) -> xmltree::Element {
+    let mut svgxml = svg_root();
+    svgxml.children = glyphs;
+    svgxml
+}
+
+/// `(glyph ID, <path id="glyphN">)` of each glyph in `order` that's in the UFO. Glyphs it doesn't
+/// have are skipped with a warning, as there's nothing to draw for them.
+pub fn glyph_elements(converter: &Converter, ufo: &Ufo, order: &[String]) -> Result<Vec<(usize, xmltree::Element)>, Glif2SvgError> {
+    let mut glyphs = vec![];
+    for (gid, name) in order.iter().enumerate() {
+        let glif_path = match ufo.glif_path_of(name) {
+            Some(glif_path) => glif_path,
+            None => {
+                eprintln!("Glyph {} of the glyph order not in {}", name, ufo.glyphs_dir.display());
+                continue
+            }
+        };
+        let glif = batch::read_glif(&glif_path)?;
+        let d = converter.path_data(converter.unframed_pen(), &glif).map_err(|e|e.at(&glif_path))?;
+
+        let mut pathxml = xmltree::Element::new("path");
+        pathxml.attributes.insert("id".to_owned(), format!("glyph{}", gid));
+        pathxml.attributes.insert("d".to_owned(), d);
+        glyphs.push((gid, pathxml));
+    }
+    Ok(glyphs)
+}
+
+/// All of the glyphs in one document.
+pub fn bundle(glyphs: Vec<(usize, xmltree::Element)>) -> xmltree::Element {
+    document(glyphs.into_iter().map(|(_, el)|xmltree::XMLNode::Element(el)).collect())
+}
+
+/// A document per glyph, `outdir/glyphN.svg`.
+pub fn write_documents(glyphs: Vec<(usize, xmltree::Element)>, outdir: &Path) -> Result<(), Glif2SvgError> {
+    fs::create_dir_all(outdir).map_err(|e|Glif2SvgError::from_io(outdir, e))?;
+    for (gid, el) in glyphs {
+        let path = outdir.join(format!("glyph{}.svg", gid));
+        let svg = write_element(&document(vec![xmltree::XMLNode::Element(el)]))?;
+        fs::write(&path, svg).map_err(|e|Glif2SvgError::from_io(&path, e))?;
+    }
+    Ok(())
+}
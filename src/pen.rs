@@ -0,0 +1,707 @@
+use crate::components::Affine;
+
+use float_cmp::approx_eq;
+use glifparser;
+use glifparser::{Handle, PointType};
+use glifparser::outline::skia::SkiaPointTransforms;
+use glifparser::outline::skia::ToSkiaPaths as _;
+use skia_safe::{Path, Point, Rect, path::Verb};
+use skia_safe::path::Iter as SkIter;
+
+use std::error::Error;
+use std::fmt;
+
+pub type XmlTreeAttribute = (String, String);
+
+/// Decimal places written unless told otherwise.
+pub const DEFAULT_PRECISION: u8 = 4;
+
+/// A number [`SVGPathPen::p`] can write.
+pub trait Coordinate {
+    fn to_f64(self) -> f64;
+}
+
+impl Coordinate for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+/// By its shortest decimal form, which is what it was in the glif before glifparser made it an
+/// `f32`, so its binary noise isn't written out.
+impl Coordinate for f32 {
+    fn to_f64(self) -> f64 {
+        self.to_string().parse().unwrap_or(self as f64)
+    }
+}
+
+/// `n` rounded half to even at `precision` decimal places, on its shortest decimal form rather
+/// than its binary value, so that `1.015` is a tie. Without trailing zeros, and never `-0`.
+fn round_decimal(n: f64, precision: u8) -> String {
+    if !n.is_finite() {
+        return n.to_string()
+    }
+    // Display never uses an exponent, and writes the shortest decimal that reads back as `n`.
+    let written = n.abs().to_string();
+    let (int, frac) = written.split_once('.').unwrap_or((&written, ""));
+    let precision = precision as usize;
+    let mut digits: Vec<u8> = int.bytes().chain(frac.bytes().take(precision)).map(|d|d - b'0').collect();
+    let rest = frac.as_bytes().get(precision..).unwrap_or_default();
+
+    let round_up = match rest.first() {
+        Some(b'6'..=b'9') => true,
+        Some(b'5') => rest[1..].iter().any(|&d|d != b'0') || digits.last().is_some_and(|d|d % 2 == 1),
+        _ => false,
+    };
+    if round_up {
+        let mut i = digits.len();
+        loop {
+            if i == 0 {
+                digits.insert(0, 1);
+                break
+            }
+            i -= 1;
+            if digits[i] == 9 {
+                digits[i] = 0;
+            } else {
+                digits[i] += 1;
+                break
+            }
+        }
+    }
+
+    let frac_len = frac.len().min(precision);
+    let (int, frac) = digits.split_at(digits.len() - frac_len);
+    let mut rounded: String = int.iter().map(|d|(b'0' + d) as char).collect();
+    let frac: String = frac.iter().map(|d|(b'0' + d) as char).collect();
+    let frac = frac.trim_end_matches('0');
+    if !frac.is_empty() {
+        rounded.push('.');
+        rounded.push_str(frac);
+    }
+    if n.is_sign_negative() && rounded.bytes().any(|d|d.is_ascii_digit() && d != b'0') {
+        rounded.insert(0, '-');
+    }
+    rounded
+}
+
+/// Conics are written as `2^CONIC_POW2` quadratics.
+const CONIC_POW2: usize = 2;
+
+/// A path the pen can't write.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PenError {
+    /// skia couldn't approximate a conic by quadratics, e.g. for a weight that isn't finite.
+    Conic { from: (f32, f32), to: (f32, f32) },
+}
+
+impl fmt::Display for PenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PenError::Conic { from, to } => write!(f, "Path has a conic from {:?} to {:?} that can't be written as quadratics", from, to),
+        }
+    }
+}
+
+impl Error for PenError {}
+
+/// Accumulates SVG path data (`d`) from skia paths, tracking the tight bounds of everything written.
+#[derive(Debug, Clone, Derivative)]
+#[derivative(Default(new="true"))]
+pub struct SVGPathPen {
+    pub path: String,
+    pub minx: f64,
+    pub maxx: f64,
+    pub miny: f64,
+    pub maxy: f64,
+    /// Decimal places, 0 for integers only.
+    #[derivative(Default(value="DEFAULT_PRECISION"))]
+    pub precision: u8,
+    pub no_viewbox: bool,
+    /// Write outlines straight from the glif's points instead of through skia, see
+    /// [`SVGPathPen::apply_contour_native`].
+    pub native: bool,
+    /// Write the shortest path data: relative coordinates where shorter, `H`/`V`/`S`/`T`
+    /// shorthands, no repeated commands, and no separators that aren't needed.
+    pub optimize: bool,
+    /// Font coordinates, y up, as SVG fonts' glyphs are drawn: no flip, no frame.
+    pub y_up: bool,
+    state: PathState,
+    /// Whether the bounds are of anything written yet
+    inked: bool,
+}
+
+/// Where the path data written so far leaves off, as written, for the optimizing writer.
+#[derive(Debug, Clone, Copy, Default)]
+struct PathState {
+    current: (f64, f64),
+    start: (f64, f64),
+    /// Last control points, if the last segment was a cubic or a quadratic
+    cubic_control: Option<(f64, f64)>,
+    quad_control: Option<(f64, f64)>,
+    command: Option<char>,
+}
+
+/// A segment for the optimizing writer, in SVG coordinates.
+#[derive(Debug, Clone, Copy)]
+enum PenSegment {
+    Move(Point),
+    Line(Point),
+    Quad(Point, Point),
+    Cubic(Point, Point, Point),
+    Close,
+}
+
+/// Joins numbers with the fewest separators: none before a `-`, nor before a `.` if the number
+/// before it already has one.
+fn push_numbers(out: &mut String, numbers: &[String]) {
+    for n in numbers {
+        let last_number: String = out.chars().rev().take_while(|c|c.is_ascii_digit() || *c == '.').collect();
+        let joined = last_number.is_empty() || n.starts_with('-') || (n.starts_with('.') && last_number.contains('.'));
+        if !joined {
+            out.push(' ');
+        }
+        out.push_str(n);
+    }
+}
+
+/// Whether a contour is open, which glifparser marks by starting it with a move.
+pub(crate) fn is_open(contour: &[glifparser::Point<()>]) -> bool {
+    contour.first().is_some_and(|p|p.ptype == PointType::Move)
+}
+
+fn reflect((cx, cy): (f64, f64), (x, y): (f64, f64)) -> (f64, f64) {
+    (2. * x - cx, 2. * y - cy)
+}
+
+impl SVGPathPen {
+    fn extend_path(&mut self, path: &str) {
+        self.path.push_str(path);
+    }
+
+    /// `(minx, miny, width, height)` of the bounds, whichever side of the origin they're on, even
+    /// if reversed (as by a negative advance width).
+    #[allow(non_snake_case)]
+    pub fn viewBox(&self) -> (f64, f64, f64, f64) {
+        (self.minx.min(self.maxx), self.miny.min(self.maxy), (self.maxx - self.minx).abs(), (self.maxy - self.miny).abs())
+    }
+
+    pub fn width(&self) -> f64 {
+        self.viewBox().2
+    }
+
+    pub fn height(&self) -> f64 {
+        self.viewBox().3
+    }
+
+    /// A number as written: rounded half to even at `precision` decimal places, without trailing
+    /// zeros.
+    pub fn p(&self, n: impl Coordinate) -> String {
+        round_decimal(n.to_f64(), self.precision)
+    }
+
+    fn size_attr_impl(&self, name: &'static str, size: f64) -> XmlTreeAttribute {
+        let name = name.to_string();
+        let size = format!("{}px", self.p(size));
+        (name, size)
+    }
+
+    pub fn px_size_attrs(&self) -> [XmlTreeAttribute; 2] {
+        [
+            self.size_attr_impl("width", self.width()),
+            self.size_attr_impl("height", self.height()),
+        ]
+    }
+
+    #[allow(non_snake_case)]
+    pub fn viewBox_str(&self) -> String {
+        let (x, y, dx, dy) = self.viewBox();
+        format!("{} {} {} {}", self.p(x), self.p(y), self.p(dx), self.p(dy))
+    }
+
+    pub fn transform_x(&self, x: f32) -> f32 {
+        x
+    }
+
+    #[allow(non_snake_case)]
+    pub fn transform_y_viewBox(&self, y: f32) -> f32 {
+        (-y) + self.maxy as f32 + self.miny as f32
+    }
+
+    pub fn transform_y_wh(&self, y: f32) -> f32 {
+        (-y) + self.miny as f32
+    }
+
+    pub fn transform_y(&self, y: f32) -> f32 {
+        if self.y_up {
+            y
+        } else if self.no_viewbox {
+            self.transform_y_wh(y)
+        } else {
+            self.transform_y_viewBox(y)
+        }
+    }
+
+    /// A point in SVG coordinates as Inkscape positions guides, from the bottom left of the page.
+    pub fn page_position(&self, x: f32, y: f32) -> (f64, f64) {
+        let (ox, oy) = if self.no_viewbox { (0., 0.) } else { (self.viewBox().0, self.viewBox().1) };
+        (x as f64 - ox, oy + self.height() - y as f64)
+    }
+
+    /// An SVG `transform` doing in this pen's y-down space what `t` does in glif space.
+    pub fn svg_matrix(&self, t: &Affine) -> String {
+        // transform_y(y) = c - y
+        let c = self.transform_y(0.);
+        format!("matrix({} {} {} {} {} {})", self.p(t.xx), self.p(-t.xy), self.p(-t.yx), self.p(t.yy), self.p(t.yx * c + t.dx), self.p(c - t.yy * c - t.dy))
+    }
+
+    fn move_to(&mut self, pt: Point) {
+        if self.optimize { return self.write_optimized(PenSegment::Move(pt)) }
+        self.extend_path(&format!("M {} {}", self.p(pt.x), self.p(pt.y)));
+    }
+
+    fn line_to(&mut self, pt: Point) {
+        if self.optimize { return self.write_optimized(PenSegment::Line(pt)) }
+        self.extend_path(&format!("L {} {}", self.p(pt.x), self.p(pt.y)));
+    }
+
+    fn curve_to(&mut self, pt: &[Point]) {
+        if self.optimize { return self.write_optimized(PenSegment::Cubic(pt[1], pt[2], pt[3])) }
+        self.extend_path(&format!("C {} {} {} {} {} {}", self.p(pt[1].x), self.p(pt[1].y), self.p(pt[2].x), self.p(pt[2].y), self.p(pt[3].x), self.p(pt[3].y)));
+    }
+
+    fn qcurve_to(&mut self, pt: &[Point]) {
+        if self.optimize { return self.write_optimized(PenSegment::Quad(pt[1], pt[2])) }
+        self.extend_path(&format!("Q {} {} {} {}", self.p(pt[1].x), self.p(pt[1].y), self.p(pt[2].x), self.p(pt[2].y)));
+    }
+
+    /// Quadratic whose control point, `pt[1]`, is the reflection of the last one.
+    fn smooth_qcurve_to(&mut self, pt: &[Point]) {
+        // Finds the reflection itself
+        if self.optimize { return self.write_optimized(PenSegment::Quad(pt[1], pt[2])) }
+        self.extend_path(&format!("T {} {}", self.p(pt[2].x), self.p(pt[2].y)));
+    }
+
+    fn close_path(&mut self) {
+        if self.optimize { return self.write_optimized(PenSegment::Close) }
+        self.extend_path("Z");
+    }
+
+    /// A coordinate as written, without a leading zero.
+    fn number(&self, n: f64) -> String {
+        let n = self.p(n);
+        if let Some(fraction) = n.strip_prefix("0.") {
+            format!(".{}", fraction)
+        } else if let Some(fraction) = n.strip_prefix("-0.") {
+            format!("-.{}", fraction)
+        } else {
+            n
+        }
+    }
+
+    /// A coordinate rounded as it's written, so relative coordinates don't accumulate error.
+    fn rounded(&self, n: f32) -> f64 {
+        self.p(n).parse().unwrap_or(n as f64)
+    }
+
+    fn rounded_point(&self, pt: Point) -> (f64, f64) {
+        (self.rounded(pt.x), self.rounded(pt.y))
+    }
+
+    fn same_point(&self, a: (f64, f64), b: (f64, f64)) -> bool {
+        self.number(a.0) == self.number(b.0) && self.number(a.1) == self.number(b.1)
+    }
+
+    /// The shorter of the absolute and relative forms of a command through `points`.
+    fn shorter_command(&self, command: char, points: &[(f64, f64)], coordinates: fn((f64, f64)) -> Vec<f64>) -> (char, Vec<String>) {
+        let (cx, cy) = self.state.current;
+        let absolute: Vec<String> = points.iter().flat_map(|&p|coordinates(p)).map(|n|self.number(n)).collect();
+        let relative: Vec<String> = points.iter().flat_map(|&(x, y)|coordinates((x - cx, y - cy))).map(|n|self.number(n)).collect();
+        let length = |numbers: &[String]| {
+            let mut s = String::new();
+            push_numbers(&mut s, numbers);
+            s.len()
+        };
+        if length(&relative) < length(&absolute) {
+            (command.to_ascii_lowercase(), relative)
+        } else {
+            (command, absolute)
+        }
+    }
+
+    fn write_optimized(&mut self, segment: PenSegment) {
+        let xy: fn((f64, f64)) -> Vec<f64> = |(x, y)|vec![x, y];
+        let state = self.state;
+        let (command, numbers, end) = match segment {
+            PenSegment::Move(p) => {
+                let p = self.rounded_point(p);
+                let (command, numbers) = self.shorter_command('M', &[p], xy);
+                self.state.start = p;
+                (command, numbers, p)
+            }
+            PenSegment::Line(p) => {
+                let p = self.rounded_point(p);
+                let (command, numbers) = if self.number(p.1) == self.number(state.current.1) {
+                    self.shorter_command('H', &[p], |(x, _)|vec![x])
+                } else if self.number(p.0) == self.number(state.current.0) {
+                    self.shorter_command('V', &[p], |(_, y)|vec![y])
+                } else {
+                    self.shorter_command('L', &[p], xy)
+                };
+                (command, numbers, p)
+            }
+            PenSegment::Cubic(c1, c2, p) => {
+                let (c1, c2, p) = (self.rounded_point(c1), self.rounded_point(c2), self.rounded_point(p));
+                let smooth = state.cubic_control.is_some_and(|c|self.same_point(reflect(c, state.current), c1));
+                let (command, numbers) = if smooth {
+                    self.shorter_command('S', &[c2, p], xy)
+                } else {
+                    self.shorter_command('C', &[c1, c2, p], xy)
+                };
+                (command, numbers, p)
+            }
+            PenSegment::Quad(c, p) => {
+                let (c, p) = (self.rounded_point(c), self.rounded_point(p));
+                let smooth = state.quad_control.is_some_and(|q|self.same_point(reflect(q, state.current), c));
+                let (command, numbers) = if smooth {
+                    self.shorter_command('T', &[p], xy)
+                } else {
+                    self.shorter_command('Q', &[c, p], xy)
+                };
+                (command, numbers, p)
+            }
+            PenSegment::Close => ('Z', vec![], state.start),
+        };
+
+        // A repeated command may be left out, except a moveto, whose repeats are linetos.
+        if state.command != Some(command) || command.eq_ignore_ascii_case(&'m') || command == 'Z' {
+            self.path.push(command);
+        }
+        push_numbers(&mut self.path, &numbers);
+
+        self.state.current = end;
+        self.state.command = Some(command);
+        self.state.cubic_control = match segment {
+            PenSegment::Cubic(_, c2, _) => Some(self.rounded_point(c2)),
+            _ => None,
+        };
+        self.state.quad_control = match segment {
+            PenSegment::Quad(c, _) => Some(self.rounded_point(c)),
+            _ => None,
+        };
+    }
+
+    /// Rational quadratic (conic) from `pt[0]` to `pt[2]`, as quadratics.
+    fn conic_to(&mut self, pt: &[Point], weight: f32) -> Result<(), PenError> {
+        let mut quads = [Point::default(); 1 + 2 * (1 << CONIC_POW2)];
+        let count = Path::convert_conic_to_quads(pt[0], pt[1], pt[2], weight, &mut quads, CONIC_POW2)
+            .ok_or(PenError::Conic { from: (pt[0].x, pt[0].y), to: (pt[2].x, pt[2].y) })?;
+        for quad in quads[..1 + 2 * count].windows(3).step_by(2) {
+            self.qcurve_to(quad);
+        }
+        Ok(())
+    }
+
+    /// Grows the bounds to take in `bounds`, or makes them `bounds` if nothing was written before.
+    fn consider_bounds(&mut self, bounds: Rect) {
+        let (left, right, top, bottom) = (bounds.left as f64, bounds.right as f64, bounds.top as f64, bounds.bottom as f64);
+        if !self.inked {
+            (self.minx, self.maxx, self.miny, self.maxy) = (left, right, top, bottom);
+            self.inked = true;
+        } else {
+            (self.minx, self.maxx) = (self.minx.min(left), self.maxx.max(right));
+            (self.miny, self.maxy) = (self.miny.min(top), self.maxy.max(bottom));
+        }
+    }
+
+    pub fn apply_outline(&mut self, outline: &glifparser::Outline<()>) -> Result<(), PenError> {
+        let skia_paths = outline.to_skia_paths(Some(SkiaPointTransforms { calc_x: &|x|self.transform_x(x), calc_y: &|y|self.transform_y(y) }));
+
+        if self.native {
+            for contour in outline {
+                self.apply_contour_native(contour);
+            }
+        } else {
+            for path in skia_paths.open.iter().chain(skia_paths.closed.iter()) {
+                let mut iter = SkIter::new(path, false);
+                while let Some((verb, pts)) = iter.next() {
+                    match verb {
+                        Verb::Move => self.move_to(pts[0]),
+                        Verb::Line => self.line_to(pts[1]),
+                        Verb::Quad => self.qcurve_to(&pts),
+                        Verb::Conic => self.conic_to(&pts, iter.conic_weight().unwrap_or(1.))?,
+                        Verb::Cubic => self.curve_to(&pts),
+                        Verb::Close => self.close_path(),
+                        Verb::Done => break,
+                    }
+                }
+            }
+        }
+
+        // Only once everything is written, as the y-transform is from the bounds the pen was
+        // framed with. From the curves' extrema, not their control points, so the bounds fit the
+        // ink.
+        for path in skia_paths.open.iter().chain(skia_paths.closed.iter()) {
+            if !path.is_empty() {
+                self.consider_bounds(path.compute_tight_bounds());
+            }
+        }
+        Ok(())
+    }
+
+    fn glif_point(&self, x: f32, y: f32) -> Point {
+        Point::new(self.transform_x(x), self.transform_y(y))
+    }
+
+    fn handle_point(&self, handle: Handle) -> Option<Point> {
+        match handle {
+            Handle::At(x, y) => Some(self.glif_point(x, y)),
+            Handle::Colocated => None,
+        }
+    }
+
+    /// Writes a contour straight from its points, in order, quadratics as `Q`. Where a quadratic's
+    /// control point reflects the last one's, the on-curve point between them is implied, as in
+    /// TrueType's runs of off-curve points, and it's written as `T`, so the run survives as such.
+    pub fn apply_contour_native(&mut self, contour: &[glifparser::Point<()>]) {
+        let first = match contour.first() {
+            Some(first) if contour.len() > 1 => first,
+            _ => return,
+        };
+        let closed = !is_open(contour);
+        let epsilon = 0.5 / 10f32.powi(self.precision as i32);
+
+        self.move_to(self.glif_point(first.x, first.y));
+        let mut last_quad_control: Option<Point> = None;
+        let closing = if closed { Some((contour.last().unwrap(), first)) } else { None };
+        for (i, (prev, cur)) in contour.windows(2).map(|w|(&w[0], &w[1])).chain(closing).enumerate() {
+            let from = self.glif_point(prev.x, prev.y);
+            let to = self.glif_point(cur.x, cur.y);
+            let (a, b) = (self.handle_point(prev.a), self.handle_point(cur.b));
+            let quad_control = match (cur.ptype, a.or(b)) {
+                (PointType::QCurve, Some(c)) => {
+                    let implied = last_quad_control.is_some_and(|lc| {
+                        approx_eq!(f32, 2. * from.x - lc.x, c.x, epsilon = epsilon) && approx_eq!(f32, 2. * from.y - lc.y, c.y, epsilon = epsilon)
+                    });
+                    if implied {
+                        self.smooth_qcurve_to(&[from, c, to]);
+                    } else {
+                        self.qcurve_to(&[from, c, to]);
+                    }
+                    Some(c)
+                }
+                (_, Some(_)) => {
+                    self.curve_to(&[from, a.unwrap_or(from), b.unwrap_or(to), to]);
+                    None
+                }
+                // The closepath draws the closing line
+                (_, None) if i + 1 == contour.len() => None,
+                (_, None) => {
+                    self.line_to(to);
+                    None
+                }
+            };
+            last_quad_control = quad_control;
+        }
+        if closed {
+            self.close_path();
+        }
+    }
+
+    /// Like `apply_outline`, but gives back each contour's path data apart, in the outline's order.
+    pub fn apply_contours(&mut self, outline: &glifparser::Outline<()>) -> Result<Vec<String>, PenError> {
+        // Each contour by the pen as it's framed now, as writing one changes the bounds.
+        let mut frame = self.clone();
+        frame.path.clear();
+        frame.state = PathState::default();
+        let mut contours = vec![];
+        for contour in outline {
+            let mut pen = frame.clone();
+            pen.apply_outline(&vec![contour.clone()])?;
+            if pen.inked {
+                self.consider_bounds(Rect::new(pen.minx as f32, pen.miny as f32, pen.maxx as f32, pen.maxy as f32));
+            }
+            contours.push(pen.path);
+        }
+        Ok(contours)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(n: impl Coordinate, precision: u8) -> String {
+        let mut pen = SVGPathPen::new();
+        pen.precision = precision;
+        pen.p(n)
+    }
+
+    /// Path data for `segments`, written plainly or optimized.
+    fn draw(optimize: bool, segments: &[PenSegment]) -> String {
+        let mut pen = SVGPathPen::new();
+        pen.optimize = optimize;
+        let mut current = Point::default();
+        for &segment in segments {
+            match segment {
+                PenSegment::Move(p) => pen.move_to(p),
+                PenSegment::Line(p) => pen.line_to(p),
+                PenSegment::Quad(c, p) => pen.qcurve_to(&[current, c, p]),
+                PenSegment::Cubic(c1, c2, p) => pen.curve_to(&[current, c1, c2, p]),
+                PenSegment::Close => pen.close_path(),
+            }
+            current = match segment {
+                PenSegment::Move(p) | PenSegment::Line(p) | PenSegment::Quad(_, p) | PenSegment::Cubic(_, _, p) => p,
+                PenSegment::Close => current,
+            };
+        }
+        pen.path
+    }
+
+    fn pt(x: f32, y: f32) -> Point {
+        Point::new(x, y)
+    }
+
+    #[test]
+    fn numbers_with_fewest_separators() {
+        let mut out = "M".to_owned();
+        push_numbers(&mut out, &["1", "-2", ".5", ".5", "3", "-.25"].map(str::to_owned));
+        assert_eq!(out, "M1-2 .5.5 3-.25");
+    }
+
+    #[test]
+    fn relative_where_shorter() {
+        assert_eq!(draw(true, &[PenSegment::Move(pt(1000., 1000.)), PenSegment::Line(pt(1001., 1002.))]), "M1000 1000l1 2");
+        assert_eq!(draw(true, &[PenSegment::Move(pt(1000., 1000.)), PenSegment::Line(pt(1., 2.))]), "M1000 1000L1 2");
+    }
+
+    #[test]
+    fn horizontal_and_vertical_lines() {
+        let segments = [PenSegment::Move(pt(0., 0.)), PenSegment::Line(pt(10., 0.)), PenSegment::Line(pt(10., 10.)), PenSegment::Line(pt(1000., 10.))];
+        assert_eq!(draw(true, &segments), "M0 0H10V10h990");
+        assert_eq!(draw(false, &segments), "M 0 0L 10 0L 10 10L 1000 10");
+    }
+
+    #[test]
+    fn smooth_curves() {
+        let cubics = [
+            PenSegment::Move(pt(0., 0.)),
+            PenSegment::Cubic(pt(0., 10.), pt(10., 10.), pt(10., 0.)),
+            PenSegment::Cubic(pt(10., -10.), pt(20., -10.), pt(20., 0.)),
+            PenSegment::Cubic(pt(25., 0.), pt(30., 5.), pt(30., 10.)),
+        ];
+        assert_eq!(draw(true, &cubics), "M0 0C0 10 10 10 10 0S20-10 20 0c5 0 10 5 10 10");
+        let quads = [PenSegment::Move(pt(0., 0.)), PenSegment::Quad(pt(5., 10.), pt(10., 0.)), PenSegment::Quad(pt(15., -10.), pt(20., 0.))];
+        assert_eq!(draw(true, &quads), "M0 0Q5 10 10 0T20 0");
+        // A T needs a quadratic before it, not a cubic
+        let mixed = [PenSegment::Move(pt(0., 0.)), PenSegment::Cubic(pt(0., 10.), pt(5., 10.), pt(10., 0.)), PenSegment::Quad(pt(15., -10.), pt(20., 0.))];
+        assert_eq!(draw(true, &mixed), "M0 0C0 10 5 10 10 0q5-10 10 0");
+    }
+
+    #[test]
+    fn repeated_commands_left_out_except_after_moveto() {
+        let segments = [PenSegment::Move(pt(0., 0.)), PenSegment::Line(pt(1., 2.)), PenSegment::Line(pt(3., 4.))];
+        assert_eq!(draw(true, &segments), "M0 0L1 2 3 4");
+        // Numbers right after a moveto would be a lineto, but a second moveto must stay one
+        assert_eq!(draw(true, &[PenSegment::Move(pt(0., 0.)), PenSegment::Move(pt(5., 5.))]), "M0 0M5 5");
+    }
+
+    #[test]
+    fn current_point_after_closepath() {
+        let segments = [
+            PenSegment::Move(pt(10., 10.)), PenSegment::Line(pt(20., 10.)), PenSegment::Line(pt(20., 20.)), PenSegment::Close,
+            PenSegment::Move(pt(11., 11.)), PenSegment::Line(pt(5., 1000.)), PenSegment::Close,
+        ];
+        assert_eq!(draw(true, &segments), "M10 10H20V20Zm1 1L5 1000Z");
+    }
+
+    #[test]
+    fn no_leading_zeros() {
+        assert_eq!(draw(true, &[PenSegment::Move(pt(10., 10.)), PenSegment::Line(pt(9.5, 10.25))]), "M10 10l-.5.25");
+        assert_eq!(draw(false, &[PenSegment::Move(pt(10., 10.)), PenSegment::Line(pt(9.5, 10.25))]), "M 10 10L 9.5 10.25");
+    }
+
+    #[test]
+    fn relative_coordinates_from_rounded_points() {
+        // Each point rounded as written, so rounding errors don't add up along the path
+        let mut pen = SVGPathPen::new();
+        pen.optimize = true;
+        pen.precision = 0;
+        pen.move_to(pt(100.4, 100.));
+        for x in [100.8, 101.2, 101.6] {
+            pen.line_to(pt(x, 100.));
+        }
+        assert_eq!(pen.path, "M100 100h1 0 1");
+    }
+
+    #[test]
+    fn decimal_ties_round_to_even() {
+        assert_eq!(p(1.015, 2), "1.02");
+        assert_eq!(p(1.025, 2), "1.02");
+        assert_eq!(p(2.665, 2), "2.66");
+        assert_eq!(p(2.675, 2), "2.68");
+        assert_eq!(p(0.00005, 4), "0");
+        assert_eq!(p(0.00015, 4), "0.0002");
+    }
+
+    #[test]
+    fn past_the_tie_rounds_away() {
+        assert_eq!(p(0.000151, 4), "0.0002");
+        assert_eq!(p(1.0249, 2), "1.02");
+        assert_eq!(p(1.0251, 2), "1.03");
+    }
+
+    #[test]
+    fn negatives() {
+        assert_eq!(p(-2.5, 0), "-2");
+        assert_eq!(p(-3.5, 0), "-4");
+        assert_eq!(p(-1.25, 1), "-1.2");
+        assert_eq!(p(-1.35, 1), "-1.4");
+    }
+
+    #[test]
+    fn no_negative_zero() {
+        assert_eq!(p(-0.0, 4), "0");
+        assert_eq!(p(-0.4, 0), "0");
+        assert_eq!(p(-0.00004, 4), "0");
+    }
+
+    #[test]
+    fn no_trailing_zeros() {
+        assert_eq!(p(12.5, 4), "12.5");
+        assert_eq!(p(3.0, 4), "3");
+        assert_eq!(p(1.05, 2), "1.05");
+        assert_eq!(p(100., 4), "100");
+    }
+
+    #[test]
+    fn precision_0() {
+        assert_eq!(p(0.5, 0), "0");
+        assert_eq!(p(1.5, 0), "2");
+        assert_eq!(p(2.5, 0), "2");
+        assert_eq!(p(12.5, 0), "12");
+        assert_eq!(p(12.51, 0), "13");
+    }
+
+    #[test]
+    fn carries() {
+        assert_eq!(p(9.99995, 4), "10");
+        assert_eq!(p(99.96, 1), "100");
+        assert_eq!(p(-0.96, 1), "-1");
+    }
+
+    #[test]
+    fn f32_by_its_shortest_decimal() {
+        // 0.1f32 is 0.100000001490116… and 1.015f32 is 1.01499998569…
+        assert_eq!(p(0.1f32, 10), "0.1");
+        assert_eq!(p(1.015f32, 2), "1.02");
+        assert_eq!(p(-283.35f32, 1), "-283.4");
+    }
+
+    #[test]
+    fn no_exponents() {
+        assert_eq!(p(1e21, 2), "1000000000000000000000");
+        assert_eq!(p(1e-7, 4), "0");
+        assert_eq!(p(1.5e-7, 7), "0.0000002");
+    }
+}
@@ -0,0 +1,71 @@
+//! Glyphs drawn by skia rather than written as SVG, framed the same.
+
+use crate::convert::{Converter, Frame, RasterSize};
+use crate::error::Glif2SvgError;
+
+use glifparser;
+use glifparser::outline::skia::SkiaPointTransforms;
+use glifparser::outline::skia::ToSkiaPaths as _;
+use skia_safe::{pdf, Canvas, Color, EncodedImageFormat, Paint, Surface};
+use skia_safe::paint::Style;
+
+fn emit_error(message: &str) -> Glif2SvgError {
+    Glif2SvgError::Emit { path: None, message: message.to_owned() }
+}
+
+/// Draws the frame's outline in its SVG coordinates: closed contours filled, open ones stroked
+/// `stroke_width` wide, as in the SVG.
+fn draw(canvas: &mut Canvas, frame: &Frame, stroke_width: f32) {
+    let pen = &frame.pen;
+    let skia_paths = frame.outline.to_skia_paths(Some(SkiaPointTransforms { calc_x: &|x|pen.transform_x(x), calc_y: &|y|pen.transform_y(y) }));
+
+    let mut paint = Paint::default();
+    paint.set_anti_alias(true);
+    paint.set_color(Color::BLACK);
+    if let Some(closed) = skia_paths.closed.as_ref() {
+        canvas.draw_path(closed, &paint);
+    }
+    if let Some(open) = skia_paths.open.as_ref() {
+        paint.set_style(Style::Stroke);
+        paint.set_stroke_width(stroke_width);
+        canvas.draw_path(open, &paint);
+    }
+}
+
+/// The glyph as a PNG on white, as large as the options' [`RasterSize`] makes its viewBox.
+pub fn png(converter: &Converter, glif: &glifparser::Glif<()>) -> Result<Vec<u8>, Glif2SvgError> {
+    let frame = converter.frame(glif)?;
+    let (minx, miny, width, height) = frame.view_box;
+    let scale = match converter.options.raster_size {
+        RasterSize::Dpi(dpi) => dpi as f64 / 96.,
+        RasterSize::Height(pixels) if height > 0. => pixels as f64 / height,
+        RasterSize::Height(_) => 1.,
+    };
+    let size = ((width * scale).ceil().max(1.) as i32, (height * scale).ceil().max(1.) as i32);
+
+    let mut surface = Surface::new_raster_n32_premul(size)
+        .ok_or_else(||emit_error(&format!("Can't make a {}×{} pixel surface", size.0, size.1)))?;
+    let canvas = surface.canvas();
+    canvas.clear(Color::WHITE);
+    canvas.scale((scale as f32, scale as f32));
+    canvas.translate((-minx as f32, -miny as f32));
+    draw(canvas, &frame, converter.options.open_stroke_width);
+
+    let png = surface.image_snapshot().encode_to_data(EncodedImageFormat::PNG)
+        .ok_or_else(||emit_error("Failed to encode PNG"))?;
+    Ok(png.as_bytes().to_vec())
+}
+
+/// The frames as a PDF of vector outlines, a page each, a font unit being a point.
+pub fn pdf(frames: &[Frame], stroke_width: f32) -> Result<Vec<u8>, Glif2SvgError> {
+    let mut document = pdf::new_document(None);
+    for frame in frames {
+        let (minx, miny, width, height) = frame.view_box;
+        let mut page = document.begin_page((width.max(1.) as f32, height.max(1.) as f32), None);
+        let canvas = page.canvas();
+        canvas.translate((-minx as f32, -miny as f32));
+        draw(canvas, frame, stroke_width);
+        document = page.end_page();
+    }
+    Ok(document.close().as_bytes().to_vec())
+}
@@ -0,0 +1,130 @@
+//! Word proofs: glyphs of a UFO set in a line by their advance widths and, optionally, kerning.
+
+use crate::batch;
+use crate::convert::Converter;
+use crate::error::Glif2SvgError;
+use crate::svg_boilerplate::svg_root;
+use crate::svgfont::{KERN1_PREFIX, KERN2_PREFIX};
+use crate::ufo::Ufo;
+
+use xmltree;
+
+use std::collections::HashMap;
+
+/// `kerning.plist`, with each glyph's kerning groups to look pairs up by.
+#[derive(Debug, Default)]
+pub struct Kerning {
+    pairs: HashMap<(String, String), f64>,
+    first_groups: HashMap<String, String>,
+    second_groups: HashMap<String, String>,
+}
+
+impl Kerning {
+    pub fn from_ufo(ufo: &Ufo) -> Result<Self, Glif2SvgError> {
+        let groups = ufo.groups().map_err(|e|Glif2SvgError::from_io(ufo.font_file("groups.plist").unwrap_or_default(), e))?;
+        let kerning = ufo.kerning().map_err(|e|Glif2SvgError::from_io(ufo.font_file("kerning.plist").unwrap_or_default(), e))?;
+
+        let mut ret = Kerning::default();
+        for (group, glyphs) in groups {
+            let side = if group.starts_with(KERN1_PREFIX) {
+                &mut ret.first_groups
+            } else if group.starts_with(KERN2_PREFIX) {
+                &mut ret.second_groups
+            } else {
+                continue
+            };
+            for glyph in glyphs {
+                side.insert(glyph, group.clone());
+            }
+        }
+        ret.pairs = kerning.into_iter().map(|(first, second, value)|((first, second), value)).collect();
+        Ok(ret)
+    }
+
+    /// The kerning between two glyphs, by the UFO's precedence: glyph–glyph, glyph–group,
+    /// group–glyph, then group–group.
+    pub fn value(&self, first: &str, second: &str) -> f64 {
+        let first_group = self.first_groups.get(first).map(String::as_str);
+        let second_group = self.second_groups.get(second).map(String::as_str);
+        let candidates = [(Some(first), Some(second)), (Some(first), second_group), (first_group, Some(second)), (first_group, second_group)];
+        candidates.iter().find_map(|pair|match *pair {
+            (Some(first), Some(second)) => self.pairs.get(&(first.to_owned(), second.to_owned())).copied(),
+            _ => None,
+        }).unwrap_or(0.)
+    }
+}
+
+/// The glyph names setting `text`, by the glyphs' unicodes. Characters the UFO has no glyph for
+/// are set as `.notdef` if it has one, else left out, with a warning.
+pub fn glyph_names(ufo: &Ufo, text: &str) -> Result<Vec<String>, Glif2SvgError> {
+    let mut cmap = HashMap::new();
+    for (name, filename) in ufo.contents.iter() {
+        let glif = batch::read_glif(&ufo.glif_path(filename))?;
+        for c in glif.unicode {
+            cmap.entry(c).or_insert_with(||name.clone());
+        }
+    }
+    let notdef = ufo.glif_path_of(".notdef").map(|_|".notdef".to_owned());
+
+    Ok(text.chars().filter_map(|c| {
+        let name = cmap.get(&c).cloned();
+        if name.is_none() {
+            eprintln!("No glyph for {:?} (U+{:04X}) in {}", c, c as u32, ufo.glyphs_dir.display());
+        }
+        name.or_else(||notdef.clone())
+    }).collect())
+}
+
+/// The glyphs named in a line, left to right from the origin on the baseline, each a
+/// `<g id="glyph-N">` translated to its position. With `kerning`, pairs are kerned. The viewBox
+/// spans the advances, and vertically the font's metrics if there are any, else the ink.
+pub fn specimen(converter: &Converter, ufo: &Ufo, names: &[String], kerning: Option<&Kerning>) -> Result<xmltree::Element, Glif2SvgError> {
+    let pen = converter.unframed_pen();
+    let mut glyphs = vec![];
+    let mut x = 0.;
+    // (minx, maxx, miny, maxy) of the ink, in the document's coordinates
+    let mut ink: Option<(f64, f64, f64, f64)> = None;
+
+    for (i, name) in names.iter().enumerate() {
+        let glif_path = ufo.glif_path_of(name).ok_or_else(||Glif2SvgError::Usage(format!("No glyph {} in {}", name, ufo.glyphs_dir.display())))?;
+        let glif = batch::read_glif(&glif_path)?;
+        let mut glyph_pen = converter.unframed_pen();
+        glyph_pen.apply_outline(&converter.flattened_outline(&glif)).map_err(|e|Glif2SvgError::from(e).at(&glif_path))?;
+
+        if !glyph_pen.path.is_empty() {
+            let (minx, miny, width, height) = glyph_pen.viewBox();
+            let (minx, maxx, maxy) = (minx + x, minx + width + x, miny + height);
+            ink = Some(match ink {
+                Some((l, r, t, b)) => (l.min(minx), r.max(maxx), t.min(miny), b.max(maxy)),
+                None => (minx, maxx, miny, maxy),
+            });
+        }
+
+        let mut pathxml = xmltree::Element::new("path");
+        pathxml.attributes.insert("d".to_owned(), glyph_pen.path);
+        let mut gxml = xmltree::Element::new("g");
+        gxml.attributes.insert("id".to_owned(), format!("glyph-{}", i));
+        gxml.attributes.insert("data-glyph-name".to_owned(), name.clone());
+        gxml.attributes.insert("transform".to_owned(), format!("translate({})", pen.p(x)));
+        gxml.children.push(xmltree::XMLNode::Element(pathxml));
+        glyphs.push(xmltree::XMLNode::Element(gxml));
+
+        x += glif.width.unwrap_or(0) as f64;
+        if let (Some(kerning), Some(next)) = (kerning, names.get(i + 1)) {
+            x += kerning.value(name, next);
+        }
+    }
+
+    let (inkl, inkr, inkt, inkb) = ink.unwrap_or((0., x, 0., 0.));
+    let (top, bottom) = match converter.metrics()? {
+        // y is down, so the ascender is at -ascender
+        Some((ascender, descender)) => (-ascender, -descender),
+        None => (inkt, inkb),
+    };
+    let (left, right) = (inkl.min(0.), inkr.max(x));
+
+    let mut svgxml = svg_root();
+    svgxml.attributes.insert("viewBox".to_owned(), format!("{} {} {} {}", pen.p(left), pen.p(top), pen.p(right - left), pen.p(bottom - top)));
+    svgxml.children = glyphs;
+    Ok(svgxml)
+}
@@ -0,0 +1,301 @@
+//! The reverse direction: SVG (such as glif2svg's own, edited in Inkscape) to glif.
+
+use crate::components::Affine;
+use crate::error::invalid_data;
+use crate::svg_boilerplate::MFEK_NS;
+use crate::svg_path::{self, Segment};
+
+use float_cmp::approx_eq;
+use glifparser;
+use glifparser::{Handle, Outline, Point, PointType};
+use xmltree;
+
+use std::collections::HashMap;
+use std::io;
+
+fn parse_length(length: &str) -> Option<f32> {
+    length.trim().trim_end_matches("px").parse().ok()
+}
+
+fn elements(el: &xmltree::Element) -> impl Iterator<Item=&xmltree::Element> {
+    el.children.iter().filter_map(xmltree::XMLNode::as_element)
+}
+
+fn index_ids<'a>(el: &'a xmltree::Element, ids: &mut HashMap<&'a str, &'a xmltree::Element>) {
+    if let Some(id) = el.attributes.get("id") {
+        ids.insert(id, el);
+    }
+    for child in elements(el) {
+        index_ids(child, ids);
+    }
+}
+
+/// Past this many levels of `<use>`s of `<use>`s, a document is assumed to be cyclic.
+const MAX_USE_DEPTH: usize = 32;
+
+/// Reads an SVG document into a glif.
+pub struct SvgReader<'a> {
+    root: &'a xmltree::Element,
+    ids: HashMap<&'a str, &'a xmltree::Element>,
+}
+
+impl<'a> SvgReader<'a> {
+    pub fn new(root: &'a xmltree::Element) -> Self {
+        let mut ids = HashMap::new();
+        index_ids(root, &mut ids);
+        SvgReader { root, ids }
+    }
+
+    /// `(minx, miny, width, height)` of the page, from the viewBox or else width/height.
+    pub fn page(&self) -> io::Result<(f32, f32, f32, f32)> {
+        if let Some(view_box) = self.root.attributes.get("viewBox") {
+            let v = view_box.split(|c: char|c.is_whitespace() || c == ',')
+                .filter(|n|!n.is_empty())
+                .map(|n|n.parse::<f32>().map_err(|_|invalid_data(format!("Malformed viewBox {}", view_box))))
+                .collect::<io::Result<Vec<f32>>>()?;
+            if v.len() != 4 {
+                return Err(invalid_data(format!("Malformed viewBox {}", view_box)))
+            }
+            return Ok((v[0], v[1], v[2], v[3]))
+        }
+
+        let length = |name: &str| self.root.attributes.get(name).and_then(|l|parse_length(l));
+        match (length("width"), length("height")) {
+            (Some(width), Some(height)) => Ok((0., 0., width, height)),
+            _ => Err(invalid_data("SVG has neither a viewBox nor a width and height".to_owned())),
+        }
+    }
+
+    /// The SVG y of the glif baseline: where glif2svg's baseline guide is, else the page's bottom.
+    pub fn baseline(&self) -> io::Result<f32> {
+        let (_, oy, _, height) = self.page()?;
+        let position = self.root.get_child("namedview")
+            .and_then(|nv|elements(nv).find(|g|g.name == "guide" && g.attributes.get("id").map(String::as_str) == Some("baseline")))
+            .and_then(|g|g.attributes.get("position"))
+            .and_then(|p|p.split(',').nth(1).and_then(|y|y.trim().parse::<f32>().ok()));
+        // Guide positions are from the bottom of the page, see `SVGPathPen::page_position`.
+        Ok(oy + height - position.unwrap_or(0.))
+    }
+
+    /// Segments of every `<path>` in `el`, in the root's coordinates. In `round_trip`, paths and
+    /// `<use>`s of components are left out, and whether anything was edited is noted.
+    fn collect(&self, el: &xmltree::Element, transform: Affine, depth: usize, round_trip: bool, collected: &mut Collected) -> io::Result<()> {
+        // Components come back from the embedded glif, and their <use>s' transforms aren't edits.
+        if round_trip && (el.attributes.contains_key("components") || el.name == "use") {
+            return Ok(())
+        }
+
+        let transform = match el.attributes.get("transform") {
+            Some(t) => {
+                collected.edited = true;
+                transform.compose(&svg_path::parse_transform(t)?)
+            }
+            None => transform,
+        };
+
+        match el.name.as_str() {
+            "path" => {
+                if let Some(d) = el.attributes.get("d") {
+                    collected.edited |= el.attributes.get("original-d") != Some(d);
+                    collected.paths.push(svg_path::parse_path_data(d)?.iter().map(|s|s.transformed(&transform)).collect());
+                }
+            }
+            "use" => {
+                let target = el.attributes.get("href")
+                    .and_then(|href|href.strip_prefix('#'))
+                    .and_then(|id|self.ids.get(id));
+                let target = match target {
+                    Some(target) if depth < MAX_USE_DEPTH => target,
+                    _ => return Ok(()),
+                };
+                let at = |name: &str|el.attributes.get(name).and_then(|l|parse_length(l)).unwrap_or(0.);
+                let transform = transform.compose(&Affine { dx: at("x"), dy: at("y"), ..Affine::IDENTITY });
+                if target.name == "symbol" {
+                    for child in elements(target) {
+                        self.collect(child, transform, depth + 1, round_trip, collected)?;
+                    }
+                } else {
+                    self.collect(target, transform, depth + 1, round_trip, collected)?;
+                }
+            }
+            // Only drawn through <use>
+            "defs" | "symbol" => (),
+            _ => {
+                for child in elements(el) {
+                    self.collect(child, transform, depth, round_trip, collected)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The paths in `g#glyph` if there is one, else all paths in the document.
+    fn collect_glyph(&self, round_trip: bool) -> io::Result<Collected> {
+        // SVG y-down to glif y-up around the baseline
+        let flip = Affine { yy: -1., dy: self.baseline()?, ..Affine::IDENTITY };
+        let mut collected = Collected::default();
+        match self.ids.get("glyph") {
+            Some(glyph) => {
+                self.collect(glyph, flip, 0, round_trip, &mut collected)?;
+                // A deleted path leaves every other path as it was.
+                let paths = glyph.attributes.get("paths").and_then(|n|n.parse::<usize>().ok());
+                collected.edited |= round_trip && paths.is_some_and(|n|n != collected.paths.len());
+            }
+            None => self.collect(self.root, flip, 0, round_trip, &mut collected)?,
+        }
+        Ok(collected)
+    }
+
+    pub fn outline(&self) -> io::Result<Outline<()>> {
+        Ok(self.collect_glyph(false)?.outline())
+    }
+
+    /// The glif embedded by glif2svg's round-trip option, if any.
+    pub fn embedded_glif(&self) -> Option<io::Result<glifparser::Glif<()>>> {
+        let glifxml = elements(self.root)
+            .filter(|e|e.name == "metadata")
+            .flat_map(elements)
+            .find(|e|e.name == "glif" && e.namespace.as_deref() == Some(MFEK_NS))?;
+        let glif_str = glifxml.get_text().unwrap_or_default();
+        Some(glifparser::glif::read(&glif_str).map_err(|e|invalid_data(format!("Failed to read embedded glif: {:?}", e))))
+    }
+
+    /// The glif, which is the embedded one if there is one and its paths weren't edited. `name`
+    /// overrides the glyph name.
+    pub fn to_glif(&self, name: Option<&str>) -> io::Result<glifparser::Glif<()>> {
+        let mut glif = match self.embedded_glif().transpose()? {
+            Some(mut glif) => {
+                let collected = self.collect_glyph(true)?;
+                if collected.edited {
+                    let mut outline = collected.outline();
+                    if let Some(original) = glif.outline.as_ref() {
+                        transfer_point_info(original, &mut outline);
+                    }
+                    glif.outline = Some(outline);
+                }
+                glif
+            }
+            None => {
+                let mut glif = glifparser::Glif::new();
+                glif.width = Some(self.page()?.2.round() as u64);
+                glif.outline = Some(self.outline()?);
+                glif
+            }
+        };
+        if let Some(name) = name {
+            glif.name = name.to_owned();
+        }
+        Ok(glif)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Collected {
+    paths: Vec<Vec<Segment>>,
+    edited: bool,
+}
+
+impl Collected {
+    fn outline(&self) -> Outline<()> {
+        self.paths.iter().flat_map(|segments|contours(segments)).collect()
+    }
+}
+
+/// After an edit, carries point names and smooth flags over to contours with as many points as
+/// they had before.
+fn transfer_point_info(original: &Outline<()>, outline: &mut Outline<()>) {
+    for (original, contour) in original.iter().zip(outline.iter_mut()) {
+        if original.len() != contour.len() {
+            continue
+        }
+        for (o, p) in original.iter().zip(contour.iter_mut()) {
+            p.name = o.name.clone();
+            p.smooth = o.smooth;
+        }
+    }
+}
+
+fn close(contour: &mut Vec<Point<()>>) {
+    if contour.len() > 1 && contour.last().map(|p|(p.x, p.y)) == contour.first().map(|p|(p.x, p.y)) {
+        // The last point is the first one, reached by the closing segment
+        let last = contour.pop().unwrap();
+        contour[0].b = last.b;
+        contour[0].ptype = last.ptype;
+    } else if let Some(first) = contour.first_mut() {
+        first.ptype = PointType::Line;
+    }
+}
+
+/// Coordinates are written rounded, so an implied on-curve point is only within this of the
+/// midpoint of the control points around it.
+const IMPLIED_EPSILON: f32 = 0.01;
+
+/// Whether `p` is the on-curve point TrueType implies between the control points `a` and `b`.
+fn implied((px, py): (f32, f32), a: (f32, f32), b: (f32, f32)) -> bool {
+    approx_eq!(f32, px, (a.0 + b.0) / 2., epsilon = IMPLIED_EPSILON) && approx_eq!(f32, py, (a.1 + b.1) / 2., epsilon = IMPLIED_EPSILON)
+}
+
+/// glifparser contours from path segments. Open contours start with a `Move`. Where a quadratic's
+/// control point reflects the last one's, as `T` writes it, the on-curve point between them is
+/// left out, so TrueType's runs of off-curve points come back as such.
+fn contours(segments: &[Segment]) -> Outline<()> {
+    let mut outline = vec![];
+    let mut contour: Vec<Point<()>> = vec![];
+    let mut start = (0., 0.);
+    let mut quad_control: Option<(f32, f32)> = None;
+
+    let mut finish = |contour: &mut Vec<Point<()>>| {
+        if contour.len() > 1 {
+            outline.push(std::mem::take(contour));
+        } else {
+            contour.clear();
+        }
+    };
+
+    for segment in segments {
+        // A segment after a closepath starts at the closed subpath's start
+        if contour.is_empty() && !matches!(segment, Segment::Move(..)) {
+            contour.push(Point::from_x_y_type(start, PointType::Move));
+        }
+        match *segment {
+            Segment::Move(p) => {
+                finish(&mut contour);
+                start = p;
+                contour.push(Point::from_x_y_type(p, PointType::Move));
+            }
+            Segment::Line(p) => contour.push(Point::from_x_y_type(p, PointType::Line)),
+            // Like glifparser, the control point is the previous point's handle
+            Segment::Quad(q, p) => {
+                if let Some(last) = contour.last_mut() {
+                    match quad_control {
+                        Some(c) if last.ptype == PointType::QCurve && implied((last.x, last.y), c, q) => {
+                            (last.x, last.y, last.ptype) = (q.0, q.1, PointType::OffCurve);
+                        }
+                        _ => last.a = Handle::At(q.0, q.1),
+                    }
+                }
+                contour.push(Point::from_x_y_type(p, PointType::QCurve));
+            }
+            Segment::Cubic(c1, c2, p) => {
+                if let Some(last) = contour.last_mut() {
+                    last.a = Handle::At(c1.0, c1.1);
+                }
+                let mut point = Point::from_x_y_type(p, PointType::Curve);
+                point.b = Handle::At(c2.0, c2.1);
+                contour.push(point);
+            }
+            Segment::Close => {
+                close(&mut contour);
+                finish(&mut contour);
+            }
+        }
+        quad_control = match *segment {
+            Segment::Quad(q, _) => Some(q),
+            _ => None,
+        };
+    }
+    finish(&mut contour);
+
+    outline
+}
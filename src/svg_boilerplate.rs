@@ -1,5 +1,6 @@
 use phf::phf_ordered_map as map;
 use phf::OrderedMap as Phf;
+use xmltree;
 
 pub static XYGRID_IDENT: &'static str = "inkscape:grid";
 pub static XYGRID: Phf<&'static str, &'static str> = map! {
@@ -19,9 +20,25 @@ pub static NAMEDVIEW: Phf<&'static str, &'static str> = map! {
     "showgrid" => "true",
 };
 
+pub static SVG_NS: &'static str = "http://www.w3.org/2000/svg";
+
+/// An SVG 1.1 `<svg>` root in the SVG namespace only, for documents not meant for Inkscape.
+pub fn svg_root() -> xmltree::Element {
+    let mut svgxml = xmltree::Element::new("svg");
+    let mut namespace = xmltree::Namespace::empty();
+    namespace.put("", SVG_NS);
+    svgxml.namespaces = Some(namespace);
+    svgxml.attributes.insert("version".to_owned(), "1.1".to_owned());
+    svgxml
+}
+
 pub static XMLNS: Phf<&'static str, &'static str> = map! {
     "" => "http://www.w3.org/2000/svg",
     "svg" => "http://www.w3.org/2000/svg",
+    "xlink" => "http://www.w3.org/1999/xlink",
     "sodipodi" => "http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd",
     "inkscape" => "http://www.inkscape.org/namespaces/inkscape",
 };
+
+/// glif2svg's own namespace, for what svg2glif needs to give back a glif exactly.
+pub static MFEK_NS: &'static str = "http://mfek.org/ns/glif2svg";
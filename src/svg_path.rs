@@ -0,0 +1,462 @@
+//! Parsing of SVG path data and `transform` attributes, for svg2glif.
+
+use crate::components::Affine;
+use crate::error::invalid_data;
+
+use std::f32::consts::PI;
+use std::io;
+
+/// A path segment, in absolute coordinates. Arcs are converted to cubics.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment {
+    Move((f32, f32)),
+    Line((f32, f32)),
+    Quad((f32, f32), (f32, f32)),
+    Cubic((f32, f32), (f32, f32), (f32, f32)),
+    Close,
+}
+
+impl Segment {
+    pub fn transformed(&self, t: &Affine) -> Segment {
+        let ap = |(x, y): (f32, f32)|t.apply(x, y);
+        match *self {
+            Segment::Move(p) => Segment::Move(ap(p)),
+            Segment::Line(p) => Segment::Line(ap(p)),
+            Segment::Quad(q, p) => Segment::Quad(ap(q), ap(p)),
+            Segment::Cubic(c1, c2, p) => Segment::Cubic(ap(c1), ap(c2), ap(p)),
+            Segment::Close => Segment::Close,
+        }
+    }
+}
+
+struct Tokens<'a> {
+    s: &'a [u8],
+    i: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(s: &'a str) -> Self {
+        Tokens { s: s.as_bytes(), i: 0 }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.i < self.s.len() && (self.s[self.i].is_ascii_whitespace() || self.s[self.i] == b',') {
+            self.i += 1;
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.i >= self.s.len()
+    }
+
+    /// The next command letter, if the next token is one.
+    fn command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        match self.s.get(self.i) {
+            Some(c) if c.is_ascii_alphabetic() && *c != b'e' && *c != b'E' => {
+                self.i += 1;
+                Some(*c)
+            }
+            _ => None,
+        }
+    }
+
+    fn number(&mut self) -> io::Result<f32> {
+        self.skip_separators();
+        let start = self.i;
+        let digits = |t: &mut Self| while t.i < t.s.len() && t.s[t.i].is_ascii_digit() { t.i += 1; };
+        if let Some(b'+' | b'-') = self.s.get(self.i) { self.i += 1; }
+        digits(self);
+        if let Some(b'.') = self.s.get(self.i) {
+            self.i += 1;
+            digits(self);
+        }
+        if let Some(b'e' | b'E') = self.s.get(self.i) {
+            self.i += 1;
+            if let Some(b'+' | b'-') = self.s.get(self.i) { self.i += 1; }
+            digits(self);
+        }
+        std::str::from_utf8(&self.s[start..self.i]).ok()
+            .and_then(|n|n.parse::<f32>().ok())
+            .ok_or_else(||invalid_data(format!("Expected a number at byte {} of path data", start)))
+    }
+
+    fn point(&mut self) -> io::Result<(f32, f32)> {
+        Ok((self.number()?, self.number()?))
+    }
+
+    /// Arc flags may be written without separators, e.g. `a1 1 0 011 1`.
+    fn flag(&mut self) -> io::Result<bool> {
+        self.skip_separators();
+        let flag = match self.s.get(self.i) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(invalid_data(format!("Expected an arc flag at byte {} of path data", self.i))),
+        };
+        self.i += 1;
+        Ok(flag)
+    }
+}
+
+fn add((x, y): (f32, f32), (dx, dy): (f32, f32)) -> (f32, f32) {
+    (x + dx, y + dy)
+}
+
+fn reflect((cx, cy): (f32, f32), (x, y): (f32, f32)) -> (f32, f32) {
+    (2. * x - cx, 2. * y - cy)
+}
+
+/// Cubics approximating an elliptical arc, per the SVG spec's endpoint to center conversion.
+fn arc_to_cubics(p0: (f32, f32), radii: (f32, f32), rotation: f32, large_arc: bool, sweep: bool, p: (f32, f32)) -> Vec<Segment> {
+    let (mut rx, mut ry) = (radii.0.abs(), radii.1.abs());
+    if p0 == p {
+        return vec![]
+    }
+    if rx == 0. || ry == 0. {
+        return vec![Segment::Line(p)]
+    }
+
+    let (sin_phi, cos_phi) = rotation.to_radians().sin_cos();
+    let (hx, hy) = ((p0.0 - p.0) / 2., (p0.1 - p.1) / 2.);
+    let x1 = cos_phi * hx + sin_phi * hy;
+    let y1 = -sin_phi * hx + cos_phi * hy;
+
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1. {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let mut coef = (num / den).max(0.).sqrt();
+    if large_arc == sweep {
+        coef = -coef;
+    }
+    let (cx1, cy1) = (coef * rx * y1 / ry, -coef * ry * x1 / rx);
+    let cx = cos_phi * cx1 - sin_phi * cy1 + (p0.0 + p.0) / 2.;
+    let cy = sin_phi * cx1 + cos_phi * cy1 + (p0.1 + p.1) / 2.;
+
+    let angle = |ux: f32, uy: f32, vx: f32, vy: f32| {
+        let a = (ux * vx + uy * vy) / ((ux * ux + uy * uy).sqrt() * (vx * vx + vy * vy).sqrt());
+        let a = a.clamp(-1., 1.).acos();
+        if ux * vy - uy * vx < 0. { -a } else { a }
+    };
+    let theta1 = angle(1., 0., (x1 - cx1) / rx, (y1 - cy1) / ry);
+    let mut dtheta = angle((x1 - cx1) / rx, (y1 - cy1) / ry, (-x1 - cx1) / rx, (-y1 - cy1) / ry);
+    if !sweep && dtheta > 0. {
+        dtheta -= 2. * PI;
+    } else if sweep && dtheta < 0. {
+        dtheta += 2. * PI;
+    }
+
+    // At most a quarter turn per cubic
+    let n = (dtheta.abs() / (PI / 2.)).ceil().max(1.) as usize;
+    let step = dtheta / n as f32;
+    let k = 4. / 3. * (step / 4.).tan();
+    let on_ellipse = |t: f32| {
+        let (sin_t, cos_t) = t.sin_cos();
+        let (ex, ey) = (rx * cos_t, ry * sin_t);
+        (cx + cos_phi * ex - sin_phi * ey, cy + sin_phi * ex + cos_phi * ey)
+    };
+    let derivative = |t: f32| {
+        let (sin_t, cos_t) = t.sin_cos();
+        let (dx, dy) = (-rx * sin_t, ry * cos_t);
+        (cos_phi * dx - sin_phi * dy, sin_phi * dx + cos_phi * dy)
+    };
+
+    (0..n).map(|i| {
+        let (t0, t1) = (theta1 + step * i as f32, theta1 + step * (i + 1) as f32);
+        let (a, b) = (on_ellipse(t0), if i + 1 == n { p } else { on_ellipse(t1) });
+        let (da, db) = (derivative(t0), derivative(t1));
+        Segment::Cubic((a.0 + k * da.0, a.1 + k * da.1), (b.0 - k * db.0, b.1 - k * db.1), b)
+    }).collect()
+}
+
+/// Parses path data (the `d` attribute) into absolute segments.
+pub fn parse_path_data(d: &str) -> io::Result<Vec<Segment>> {
+    let mut tokens = Tokens::new(d);
+    let mut segments = vec![];
+    let mut current = (0., 0.);
+    let mut start = (0., 0.);
+    // Last control points, for S and T
+    let mut last_cubic_control: Option<(f32, f32)> = None;
+    let mut last_quad_control: Option<(f32, f32)> = None;
+    let mut previous: Option<u8> = None;
+
+    while !tokens.at_end() {
+        let command = match (tokens.command(), previous) {
+            (Some(c), _) => c,
+            // Coordinates after a moveto are implicit linetos
+            (None, Some(b'M')) => b'L',
+            (None, Some(b'm')) => b'l',
+            (None, Some(c)) if c != b'Z' && c != b'z' => c,
+            _ => return Err(invalid_data(format!("Expected a path command at byte {} of path data", tokens.i))),
+        };
+        let relative = command.is_ascii_lowercase();
+        let origin = if relative { current } else { (0., 0.) };
+        let mut cubic_control = None;
+        let mut quad_control = None;
+
+        match command.to_ascii_uppercase() {
+            b'M' => {
+                current = add(origin, tokens.point()?);
+                start = current;
+                segments.push(Segment::Move(current));
+            }
+            b'L' => {
+                current = add(origin, tokens.point()?);
+                segments.push(Segment::Line(current));
+            }
+            b'H' => {
+                current = (tokens.number()? + origin.0, current.1);
+                segments.push(Segment::Line(current));
+            }
+            b'V' => {
+                current = (current.0, tokens.number()? + origin.1);
+                segments.push(Segment::Line(current));
+            }
+            b'C' => {
+                let c1 = add(origin, tokens.point()?);
+                let c2 = add(origin, tokens.point()?);
+                current = add(origin, tokens.point()?);
+                segments.push(Segment::Cubic(c1, c2, current));
+                cubic_control = Some(c2);
+            }
+            b'S' => {
+                let c1 = last_cubic_control.map(|c|reflect(c, current)).unwrap_or(current);
+                let c2 = add(origin, tokens.point()?);
+                current = add(origin, tokens.point()?);
+                segments.push(Segment::Cubic(c1, c2, current));
+                cubic_control = Some(c2);
+            }
+            b'Q' => {
+                let q = add(origin, tokens.point()?);
+                let p = add(origin, tokens.point()?);
+                segments.push(Segment::Quad(q, p));
+                current = p;
+                quad_control = Some(q);
+            }
+            b'T' => {
+                let q = last_quad_control.map(|c|reflect(c, current)).unwrap_or(current);
+                let p = add(origin, tokens.point()?);
+                segments.push(Segment::Quad(q, p));
+                current = p;
+                quad_control = Some(q);
+            }
+            b'A' => {
+                let radii = tokens.point()?;
+                let rotation = tokens.number()?;
+                let large_arc = tokens.flag()?;
+                let sweep = tokens.flag()?;
+                let p = add(origin, tokens.point()?);
+                segments.extend(arc_to_cubics(current, radii, rotation, large_arc, sweep, p));
+                current = p;
+            }
+            b'Z' => {
+                segments.push(Segment::Close);
+                current = start;
+            }
+            _ => return Err(invalid_data(format!("Unknown path command {}", command as char))),
+        }
+
+        last_cubic_control = cubic_control;
+        last_quad_control = quad_control;
+        previous = Some(command);
+    }
+
+    Ok(segments)
+}
+
+/// Parses a `transform` attribute into the single transformation it amounts to.
+pub fn parse_transform(transform: &str) -> io::Result<Affine> {
+    let mut result = Affine::IDENTITY;
+    let mut rest = transform.trim();
+
+    while !rest.is_empty() {
+        let open = rest.find('(').ok_or_else(||invalid_data(format!("Malformed transform {}", transform)))?;
+        let close = rest.find(')').ok_or_else(||invalid_data(format!("Malformed transform {}", transform)))?;
+        let name = rest[..open].trim_matches(|c: char|c.is_whitespace() || c == ',');
+        let args = rest[open+1..close]
+            .split(|c: char|c.is_whitespace() || c == ',')
+            .filter(|a|!a.is_empty())
+            .map(|a|a.parse::<f32>().map_err(|_|invalid_data(format!("Malformed transform {}", transform))))
+            .collect::<io::Result<Vec<f32>>>()?;
+        let arg = |i: usize|args.get(i).copied();
+
+        let t = match (name, args.len()) {
+            ("matrix", 6) => Affine { xx: args[0], xy: args[1], yx: args[2], yy: args[3], dx: args[4], dy: args[5] },
+            ("translate", 1 | 2) => Affine { dx: args[0], dy: arg(1).unwrap_or(0.), ..Affine::IDENTITY },
+            ("scale", 1 | 2) => Affine { xx: args[0], yy: arg(1).unwrap_or(args[0]), ..Affine::IDENTITY },
+            ("rotate", 1 | 3) => {
+                let (sin, cos) = args[0].to_radians().sin_cos();
+                let rotation = Affine { xx: cos, xy: sin, yx: -sin, yy: cos, ..Affine::IDENTITY };
+                let (cx, cy) = (arg(1).unwrap_or(0.), arg(2).unwrap_or(0.));
+                Affine { dx: cx, dy: cy, ..Affine::IDENTITY }
+                    .compose(&rotation)
+                    .compose(&Affine { dx: -cx, dy: -cy, ..Affine::IDENTITY })
+            }
+            ("skewX", 1) => Affine { yx: args[0].to_radians().tan(), ..Affine::IDENTITY },
+            ("skewY", 1) => Affine { xy: args[0].to_radians().tan(), ..Affine::IDENTITY },
+            _ => return Err(invalid_data(format!("Unsupported transform {}", transform))),
+        };
+
+        result = result.compose(&t);
+        rest = rest[close+1..].trim_start();
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close_to(a: (f32, f32), b: (f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3
+    }
+
+    fn end(segment: &Segment) -> (f32, f32) {
+        match *segment {
+            Segment::Move(p) | Segment::Line(p) | Segment::Quad(_, p) | Segment::Cubic(_, _, p) => p,
+            Segment::Close => panic!("A closepath has no end point of its own"),
+        }
+    }
+
+    #[test]
+    fn implicit_repeated_commands() {
+        assert_eq!(parse_path_data("M0 0 10 0 10 10").unwrap(), vec![
+            Segment::Move((0., 0.)), Segment::Line((10., 0.)), Segment::Line((10., 10.)),
+        ]);
+        assert_eq!(parse_path_data("m1 1 2 2l1 0 0 1").unwrap(), vec![
+            Segment::Move((1., 1.)), Segment::Line((3., 3.)), Segment::Line((4., 3.)), Segment::Line((4., 4.)),
+        ]);
+        assert_eq!(parse_path_data("M0 0C0 1 1 1 1 0 1-1 2-1 2 0").unwrap(), vec![
+            Segment::Move((0., 0.)),
+            Segment::Cubic((0., 1.), (1., 1.), (1., 0.)),
+            Segment::Cubic((1., -1.), (2., -1.), (2., 0.)),
+        ]);
+    }
+
+    #[test]
+    fn smooth_cubic_reflects_last_control() {
+        let segments = parse_path_data("M0 0 C0 10 10 10 10 0 S20 -10 20 0 s10 10 10 0").unwrap();
+        assert_eq!(segments[2], Segment::Cubic((10., -10.), (20., -10.), (20., 0.)));
+        assert_eq!(segments[3], Segment::Cubic((20., 10.), (30., 10.), (30., 0.)));
+    }
+
+    #[test]
+    fn smooth_cubic_without_cubic_before() {
+        let segments = parse_path_data("M0 0 L10 0 S20 10 20 0").unwrap();
+        assert_eq!(segments[2], Segment::Cubic((10., 0.), (20., 10.), (20., 0.)));
+    }
+
+    #[test]
+    fn smooth_quadratic_reflects_last_control() {
+        let segments = parse_path_data("M0 0 Q5 10 10 0 T20 0 t10 0").unwrap();
+        assert_eq!(segments[2], Segment::Quad((15., -10.), (20., 0.)));
+        assert_eq!(segments[3], Segment::Quad((25., 10.), (30., 0.)));
+        // A T after anything but a quadratic has its control point on the current point
+        let segments = parse_path_data("M0 0 C0 10 10 10 10 0 T20 0").unwrap();
+        assert_eq!(segments[2], Segment::Quad((10., 0.), (20., 0.)));
+    }
+
+    #[test]
+    fn relative_after_closepath() {
+        assert_eq!(parse_path_data("M10 10 h10 v10 z l5 5").unwrap(), vec![
+            Segment::Move((10., 10.)), Segment::Line((20., 10.)), Segment::Line((20., 20.)), Segment::Close,
+            Segment::Line((15., 15.)),
+        ]);
+        assert_eq!(parse_path_data("M10 10 h10 v10 z m5 5 h1").unwrap()[4..], [
+            Segment::Move((15., 15.)), Segment::Line((16., 15.)),
+        ]);
+    }
+
+    #[test]
+    fn compact_numbers() {
+        assert_eq!(parse_path_data("M1-2L.5.5l-.5-.25").unwrap(), vec![
+            Segment::Move((1., -2.)), Segment::Line((0.5, 0.5)), Segment::Line((0., 0.25)),
+        ]);
+        assert_eq!(parse_path_data("M1e2-3,+4E-1 5").unwrap(), vec![
+            Segment::Move((100., -3.)), Segment::Line((0.4, 5.)),
+        ]);
+    }
+
+    #[test]
+    fn arc_flags_without_separators() {
+        let segments = parse_path_data("M0 0a1 1 0 011 1").unwrap();
+        assert!(!segments.is_empty());
+        assert_eq!(end(segments.last().unwrap()), (1., 1.));
+        assert_eq!(segments, parse_path_data("M0 0 a 1 1 0 0 1 1 1").unwrap());
+    }
+
+    #[test]
+    fn arc_as_cubics() {
+        // Half a circle around (5, 0), clockwise on screen, so through (5, -5)
+        let segments = parse_path_data("M0 0 A5 5 0 0 1 10 0").unwrap();
+        assert_eq!(segments.len(), 3);
+        assert!(close_to(end(&segments[1]), (5., -5.)), "{:?}", segments);
+        assert_eq!(end(&segments[2]), (10., 0.));
+        // The other sweep goes through (5, 5)
+        let segments = parse_path_data("M0 0 A5 5 0 0 0 10 0").unwrap();
+        assert!(close_to(end(&segments[1]), (5., 5.)), "{:?}", segments);
+        // Radii too small are scaled up, zero radii make a line
+        let segments = parse_path_data("M0 0 A1 1 0 0 1 10 0").unwrap();
+        assert!(close_to(end(&segments[1]), (5., -5.)), "{:?}", segments);
+        assert_eq!(parse_path_data("M0 0 A0 5 0 0 1 10 0").unwrap()[1], Segment::Line((10., 0.)));
+    }
+
+    #[test]
+    fn glif2svg_path_data() {
+        // As glif2svg writes a 200×100 rectangle and a curve, plainly and with -O
+        let plain = parse_path_data("M 100 -200 L 300 -200 L 300 -100 L 100 -100 ZM 0 0 C 0 -55.2285 44.7715 -100 100 -100 Q 150 -100 200 -50 T 300 0 Z").unwrap();
+        let optimized = parse_path_data("M100-200H300V-100H100ZM0 0C0-55.2285 44.7715-100 100-100Q150-100 200-50T300 0Z").unwrap();
+        assert_eq!(plain, vec![
+            Segment::Move((100., -200.)), Segment::Line((300., -200.)), Segment::Line((300., -100.)), Segment::Line((100., -100.)), Segment::Close,
+            Segment::Move((0., 0.)), Segment::Cubic((0., -55.2285), (44.7715, -100.), (100., -100.)),
+            Segment::Quad((150., -100.), (200., -50.)), Segment::Quad((250., 0.), (300., 0.)), Segment::Close,
+        ]);
+        assert_eq!(plain, optimized);
+    }
+
+    #[test]
+    fn malformed_path_data() {
+        assert!(parse_path_data("10 10").is_err());
+        assert!(parse_path_data("M0 0 Z 5 5").is_err());
+        assert!(parse_path_data("M0 0 X 5 5").is_err());
+        assert!(parse_path_data("M0 0 L5").is_err());
+        assert!(parse_path_data("M0 0 A1 1 0 2 1 1 1").is_err());
+    }
+
+    #[test]
+    fn transform_order() {
+        // Applied right to left: scaled, then translated
+        let t = parse_transform("translate(10 0) scale(2)").unwrap();
+        assert_eq!(t.apply(1., 1.), (12., 2.));
+        let t = parse_transform("scale(2),translate(10)").unwrap();
+        assert_eq!(t.apply(1., 1.), (22., 2.));
+        let t = parse_transform("matrix(1 0 0 -1 5 6)").unwrap();
+        assert_eq!(t.apply(1., 1.), (6., 5.));
+    }
+
+    #[test]
+    fn rotate_around_center() {
+        let t = parse_transform("rotate(90 10 0)").unwrap();
+        assert!(close_to(t.apply(10., 0.), (10., 0.)));
+        assert!(close_to(t.apply(20., 0.), (10., 10.)));
+        let t = parse_transform("rotate(90)").unwrap();
+        assert!(close_to(t.apply(10., 0.), (0., 10.)));
+        // rotate(a cx cy) is translate(cx cy) rotate(a) translate(-cx -cy)
+        let composed = parse_transform("translate(10 5) rotate(30) translate(-10 -5)").unwrap();
+        let t = parse_transform("rotate(30 10 5)").unwrap();
+        assert!(close_to(t.apply(3., 4.), composed.apply(3., 4.)));
+    }
+
+    #[test]
+    fn malformed_transforms() {
+        assert!(parse_transform("translate(1").is_err());
+        assert!(parse_transform("rotate(1 2)").is_err());
+        assert!(parse_transform("perspective(1)").is_err());
+    }
+}
@@ -0,0 +1,118 @@
+//! A whole UFO as one SVG 1.1 font (`<font>`, `<glyph>`s and `<hkern>`s).
+
+use crate::batch;
+use crate::convert::Converter;
+use crate::error::Glif2SvgError;
+use crate::fontinfo::FontInfo;
+use crate::svg_boilerplate::svg_root;
+use crate::ufo::Ufo;
+
+use xmltree;
+
+use std::collections::HashMap;
+
+/// Kerning group prefixes, for the first and the second glyph of a pair.
+pub(crate) const KERN1_PREFIX: &str = "public.kern1.";
+pub(crate) const KERN2_PREFIX: &str = "public.kern2.";
+
+fn element(name: &str, attributes: &[(&str, String)]) -> xmltree::Element {
+    let mut el = xmltree::Element::new(name);
+    for (k, v) in attributes {
+        el.attributes.insert((*k).to_owned(), v.clone());
+    }
+    el
+}
+
+/// The glyph names a kerning pair side stands for, a group's members or a single glyph.
+fn kerning_glyphs(side: &str, prefix: &str, groups: &HashMap<String, Vec<String>>) -> Vec<String> {
+    match groups.get(side) {
+        Some(glyphs) if side.starts_with(prefix) => glyphs.clone(),
+        _ => vec![side.to_owned()],
+    }
+}
+
+/// Every glyph of `ufo` in an SVG font, with `converter`'s options for path data. Glyph outlines are
+/// in font coordinates, y up, as SVG fonts have them.
+pub fn svg_font(converter: &Converter, ufo: &Ufo) -> Result<xmltree::Element, Glif2SvgError> {
+    let fontinfo = match ufo.fontinfo_path() {
+        Some(path) => FontInfo::from_file(&path).map_err(|e|Glif2SvgError::from_io(&path, e))?,
+        None => FontInfo::default(),
+    };
+    let units_per_em = fontinfo.units_per_em.unwrap_or(1000.);
+    let pen = || {
+        let mut pen = converter.unframed_pen();
+        pen.y_up = true;
+        pen
+    };
+    let family_name = fontinfo.family_name.clone().unwrap_or_else(||"glif2svg".to_owned());
+
+    let mut fontxml = element("font", &[
+        ("id", family_name.replace(char::is_whitespace, "-")),
+        ("horiz-adv-x", pen().p(units_per_em)),
+    ]);
+
+    let mut facexml = element("font-face", &[
+        ("font-family", family_name),
+        ("units-per-em", pen().p(units_per_em)),
+    ]);
+    let metrics = [("ascent", fontinfo.ascender), ("descent", fontinfo.descender), ("x-height", fontinfo.x_height), ("cap-height", fontinfo.cap_height)];
+    for (name, value) in metrics {
+        if let Some(value) = value {
+            facexml.attributes.insert(name.to_owned(), pen().p(value));
+        }
+    }
+    fontxml.children.push(xmltree::XMLNode::Element(facexml));
+
+    let mut missing = false;
+    for (name, filename) in ufo.contents.iter() {
+        let glif_path = ufo.glif_path(filename);
+        let glif = batch::read_glif(&glif_path)?;
+        let d = converter.path_data(pen(), &glif).map_err(|e|e.at(&glif_path))?;
+        let advance = pen().p(glif.width.unwrap_or(0) as f64);
+
+        if name == ".notdef" {
+            let missingxml = element("missing-glyph", &[("horiz-adv-x", advance.clone()), ("d", d.clone())]);
+            fontxml.children.insert(1, xmltree::XMLNode::Element(missingxml));
+            missing = true;
+        }
+
+        // A <glyph> has one unicode, so glyphs with more are repeated.
+        let unicodes: Vec<Option<String>> = if glif.unicode.is_empty() {
+            vec![None]
+        } else {
+            glif.unicode.iter().map(|c|Some(c.to_string())).collect()
+        };
+        for unicode in unicodes {
+            let mut glyphxml = element("glyph", &[("glyph-name", name.clone()), ("horiz-adv-x", advance.clone())]);
+            if let Some(unicode) = unicode {
+                glyphxml.attributes.insert("unicode".to_owned(), unicode);
+            }
+            if !d.is_empty() {
+                glyphxml.attributes.insert("d".to_owned(), d.clone());
+            }
+            fontxml.children.push(xmltree::XMLNode::Element(glyphxml));
+        }
+    }
+    if !missing {
+        let missingxml = element("missing-glyph", &[("horiz-adv-x", pen().p(units_per_em / 2.))]);
+        fontxml.children.insert(1, xmltree::XMLNode::Element(missingxml));
+    }
+
+    let groups = ufo.groups().map_err(|e|Glif2SvgError::from_io(ufo.font_file("groups.plist").unwrap_or_default(), e))?;
+    let kerning = ufo.kerning().map_err(|e|Glif2SvgError::from_io(ufo.font_file("kerning.plist").unwrap_or_default(), e))?;
+    for (first, second, value) in kerning {
+        let g1 = kerning_glyphs(&first, KERN1_PREFIX, &groups).join(",");
+        let g2 = kerning_glyphs(&second, KERN2_PREFIX, &groups).join(",");
+        // SVG's k is taken off the advance, UFO kerning is added to it.
+        let hkernxml = element("hkern", &[("g1", g1), ("g2", g2), ("k", pen().p(-value))]);
+        fontxml.children.push(xmltree::XMLNode::Element(hkernxml));
+    }
+
+    let mut defsxml = xmltree::Element::new("defs");
+    defsxml.children.push(xmltree::XMLNode::Element(fontxml));
+
+    let mut svgxml = svg_root();
+    svgxml.children.push(xmltree::XMLNode::Element(defsxml));
+
+    Ok(svgxml)
+}
@@ -0,0 +1,123 @@
+use crate::error::invalid_data;
+
+use plist;
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A UFO's default glyphs layer, as listed by its `contents.plist`.
+#[derive(Debug, Clone)]
+pub struct Ufo {
+    /// The `.ufo` directory, if the glyphs directory is in one.
+    pub path: Option<PathBuf>,
+    pub glyphs_dir: PathBuf,
+    /// `(glyph name, glif filename)` in `contents.plist` order.
+    pub contents: Vec<(String, String)>,
+    names: HashMap<String, usize>,
+}
+
+pub(crate) fn plist_error(e: plist::Error) -> io::Error {
+    e.into_io().unwrap_or_else(|e|io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// A plist number, which may be an integer or a real.
+pub(crate) fn plist_number(v: &plist::Value) -> Option<f64> {
+    v.as_real().or_else(||v.as_signed_integer().map(|i|i as f64))
+}
+
+impl Ufo {
+    /// Opens either a `.ufo` directory or a glyphs directory directly.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let (ufo_path, glyphs_dir) = if path.join("contents.plist").is_file() {
+            let parent = path.parent().filter(|p|p.join("metainfo.plist").is_file());
+            (parent.map(Path::to_path_buf), path.to_path_buf())
+        } else {
+            (Some(path.to_path_buf()), path.join("glyphs"))
+        };
+
+        let contents_path = glyphs_dir.join("contents.plist");
+        let contents = plist::Value::from_file(&contents_path).map_err(plist_error)?
+            .into_dictionary()
+            .ok_or_else(||invalid_data(format!("{} is not a dictionary", contents_path.display())))?;
+        let contents = contents.into_iter().map(|(name, filename)| {
+            match filename.into_string() {
+                Some(filename) => Ok((name, filename)),
+                None => Err(invalid_data(format!("{} has a non-string filename for glyph {}", contents_path.display(), name))),
+            }
+        }).collect::<io::Result<Vec<_>>>()?;
+
+        let names = contents.iter().enumerate().map(|(i, (name, _))|(name.clone(), i)).collect();
+
+        Ok(Ufo { path: ufo_path, glyphs_dir, contents, names })
+    }
+
+    /// The glyphs directory a glif is in, if it has a `contents.plist`.
+    pub fn containing(glif_path: impl AsRef<Path>) -> Option<Self> {
+        let glyphs_dir = glif_path.as_ref().parent()?;
+        if !glyphs_dir.join("contents.plist").is_file() {
+            return None
+        }
+        Ufo::open(glyphs_dir).ok()
+    }
+
+    /// The path of the glif for a glyph name, e.g. a component's base.
+    pub fn glif_path_of(&self, name: &str) -> Option<PathBuf> {
+        self.names.get(name).map(|&i|self.glif_path(&self.contents[i].1))
+    }
+
+    pub fn glif_path(&self, filename: &str) -> PathBuf {
+        self.glyphs_dir.join(filename)
+    }
+
+    /// `fontinfo.plist`, if the glyphs directory is in a UFO that has one.
+    pub fn fontinfo_path(&self) -> Option<PathBuf> {
+        self.font_file("fontinfo.plist")
+    }
+
+    /// A file at the top of the UFO, if the glyphs directory is in one and it has the file.
+    pub fn font_file(&self, name: &str) -> Option<PathBuf> {
+        self.path.as_ref().map(|p|p.join(name)).filter(|p|p.is_file())
+    }
+
+    /// A top level plist of the UFO as a dictionary, or `None` if it doesn't have one.
+    fn font_plist(&self, name: &str) -> io::Result<Option<plist::Dictionary>> {
+        let path = match self.font_file(name) {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        plist::Value::from_file(&path).map_err(plist_error)?
+            .into_dictionary()
+            .map(Some)
+            .ok_or_else(||invalid_data(format!("{} is not a dictionary", path.display())))
+    }
+
+    /// `public.glyphOrder` from `lib.plist`, if it has one.
+    pub fn glyph_order(&self) -> io::Result<Option<Vec<String>>> {
+        let lib = self.font_plist("lib.plist")?.unwrap_or_default();
+        Ok(lib.get("public.glyphOrder").and_then(plist::Value::as_array).map(|names| {
+            names.iter().filter_map(plist::Value::as_string).map(str::to_owned).collect()
+        }))
+    }
+
+    /// `groups.plist`: group name to glyph names.
+    pub fn groups(&self) -> io::Result<HashMap<String, Vec<String>>> {
+        Ok(self.font_plist("groups.plist")?.unwrap_or_default().into_iter().map(|(group, glyphs)| {
+            let glyphs = glyphs.as_array()
+                .map(|glyphs|glyphs.iter().filter_map(plist::Value::as_string).map(str::to_owned).collect())
+                .unwrap_or_default();
+            (group, glyphs)
+        }).collect())
+    }
+
+    /// `kerning.plist` as `(first, second, value)` pairs in file order, first and second being
+    /// glyph or group names.
+    pub fn kerning(&self) -> io::Result<Vec<(String, String, f64)>> {
+        Ok(self.font_plist("kerning.plist")?.unwrap_or_default().into_iter().flat_map(|(first, seconds)| {
+            seconds.into_dictionary().unwrap_or_default().into_iter().filter_map(move |(second, value)| {
+                Some((first.clone(), second, plist_number(&value)?))
+            })
+        }).collect())
+    }
+}
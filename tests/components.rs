@@ -0,0 +1,112 @@
+use glif2svg::svg2glif::SvgReader;
+use glif2svg::{ComponentMode, Converter, MetricsSource, Options, Ufo};
+
+use std::fs;
+
+/// A glyphs directory of `glifs`, `(name, outline)`, under `name` in the temporary directory.
+fn ufo(name: &str, glifs: &[(&str, &str)]) -> Ufo {
+    let glyphs_dir = std::env::temp_dir().join(format!("glif2svg-test-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&glyphs_dir).unwrap();
+    let contents: String = glifs.iter().map(|(name, _)|format!("<key>{0}</key><string>{0}.glif</string>", name)).collect();
+    fs::write(glyphs_dir.join("contents.plist"), format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0"><dict>{}</dict></plist>
+"#, contents)).unwrap();
+    for (name, outline) in glifs {
+        fs::write(glyphs_dir.join(format!("{}.glif", name)), glif_str(name, outline)).unwrap();
+    }
+    Ufo::open(&glyphs_dir).unwrap()
+}
+
+fn glif_str(name: &str, outline: &str) -> String {
+    format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="{}" format="2">
+  <advance width="500"/>
+  <outline>{}</outline>
+</glyph>
+"#, name, outline)
+}
+
+const TRIANGLE: &str = r#"
+    <contour>
+      <point x="100" y="500" type="line"/>
+      <point x="200" y="500" type="line"/>
+      <point x="250" y="650" type="line"/>
+    </contour>"#;
+
+/// Every `<use>`'s `href` in `el`.
+fn hrefs(el: &xmltree::Element, out: &mut Vec<String>) {
+    if el.name == "use" {
+        out.push(el.attributes["href"].clone());
+    }
+    for child in el.children.iter().filter_map(xmltree::XMLNode::as_element) {
+        hrefs(child, out);
+    }
+}
+
+#[test]
+fn cyclic_use_left_out() {
+    let a = format!(r#"{}<component base="b"/>"#, TRIANGLE);
+    let b = format!(r#"{}<component base="a" xOffset="10"/>"#, TRIANGLE);
+    let ufo = ufo("cyclic-use", &[("a", &a), ("b", &b)]);
+
+    let mut options = Options::new();
+    options.components = ComponentMode::Use;
+    options.ufo = Some(ufo);
+    let glif = glifparser::glif::read(&glif_str("a", &a)).unwrap();
+    let svg = Converter::new(options).to_element(&glif).unwrap();
+
+    let defs = svg.get_child("defs").unwrap();
+    let symbols: Vec<&xmltree::Element> = defs.children.iter().filter_map(xmltree::XMLNode::as_element).collect();
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].attributes["id"], "glyph-b");
+    // b's component a is the glyph being written, which uses b.
+    let mut symbol_hrefs = vec![];
+    hrefs(symbols[0], &mut symbol_hrefs);
+    assert!(symbol_hrefs.is_empty(), "{:?}", symbol_hrefs);
+    let mut glyph_hrefs = vec![];
+    hrefs(svg.get_child("g").unwrap(), &mut glyph_hrefs);
+    assert_eq!(glyph_hrefs, vec!["#glyph-b"]);
+}
+
+/// Each point's position and handles, read back from the SVG the glif is written as.
+fn read_back(options: Options, glif: &glifparser::Glif<()>) -> Vec<(f32, f32)> {
+    let svg = Converter::new(options).to_element(glif).unwrap();
+    let outline = SvgReader::new(&svg).outline().unwrap();
+    outline.iter().flatten().flat_map(|p| {
+        let handles = [p.a, p.b].into_iter().filter_map(|h|match h {
+            glifparser::Handle::At(x, y) => Some((x, y)),
+            glifparser::Handle::Colocated => None,
+        });
+        std::iter::once((p.x, p.y)).chain(handles).collect::<Vec<_>>()
+    }).collect()
+}
+
+/// A `<use>` of a scaled and skewed component draws what flattening it does.
+fn assert_use_matches_flattened(name: &str, metrics: MetricsSource) {
+    let aacute = format!(r#"{}<component base="acute" xScale="2" xyScale="0.5" yxScale="0.25" yScale="1.5" xOffset="30" yOffset="-20"/>"#, TRIANGLE);
+    let ufo = ufo(name, &[("acute", TRIANGLE), ("aacute", &aacute)]);
+    let glif = glifparser::glif::read(&glif_str("aacute", &aacute)).unwrap();
+
+    let mut options = Options::new();
+    options.metrics = metrics;
+    options.ufo = Some(ufo);
+    let flattened = read_back(options.clone(), &glif);
+    options.components = ComponentMode::Use;
+    let used = read_back(options, &glif);
+
+    assert_eq!(used.len(), flattened.len(), "{:?} and {:?}", used, flattened);
+    for (u, f) in used.iter().zip(flattened.iter()) {
+        assert!((u.0 - f.0).abs() < 1e-2 && (u.1 - f.1).abs() < 1e-2, "{:?} and {:?}", used, flattened);
+    }
+}
+
+#[test]
+fn use_matches_flattened_framed_by_bounds() {
+    assert_use_matches_flattened("use-bounds", MetricsSource::Bounds);
+}
+
+#[test]
+fn use_matches_flattened_framed_by_metrics() {
+    assert_use_matches_flattened("use-metrics", MetricsSource::Fixed { ascender: 800., descender: -200. });
+}
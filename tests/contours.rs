@@ -0,0 +1,69 @@
+use glif2svg::{ContourMode, Converter, MetricsSource, Options, OutlineMode};
+
+/// Two closed contours at different heights and an open one.
+const GLIF: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="test" format="2">
+  <advance width="500"/>
+  <outline>
+    <contour>
+      <point x="100" y="100" type="line"/>
+      <point x="300" y="100" type="line"/>
+      <point x="300" y="200" type="line"/>
+      <point x="100" y="200" type="line"/>
+    </contour>
+    <contour>
+      <point x="0" y="400" type="line"/>
+      <point x="0" y="700"/>
+      <point x="400" y="700"/>
+      <point x="400" y="400" type="curve"/>
+    </contour>
+    <contour>
+      <point x="50" y="-100" type="move"/>
+      <point x="450" y="-50" type="line"/>
+    </contour>
+  </outline>
+</glyph>"#;
+
+/// The `d` of every path in the element, in document order, joined together.
+fn joined_path_data(el: &xmltree::Element, out: &mut String) {
+    if el.name == "path" {
+        out.push_str(&el.attributes["d"]);
+    }
+    for child in el.children.iter().filter_map(xmltree::XMLNode::as_element) {
+        joined_path_data(child, out);
+    }
+}
+
+fn path_data(contours: ContourMode, outline: OutlineMode, metrics: MetricsSource) -> String {
+    let glif = glifparser::glif::read(GLIF).unwrap();
+    let mut options = Options::new();
+    options.contours = contours;
+    options.outline = outline;
+    options.metrics = metrics;
+    let svg = Converter::new(options).to_element(&glif).unwrap();
+    let mut d = String::new();
+    joined_path_data(svg.get_child("g").unwrap(), &mut d);
+    d
+}
+
+fn assert_split_joins_back(outline: OutlineMode, metrics: MetricsSource) {
+    let joined = path_data(ContourMode::Joined, outline, metrics.clone());
+    let split = path_data(ContourMode::Split, outline, metrics);
+    assert_eq!(split, joined);
+}
+
+#[test]
+fn split_framed_by_bounds() {
+    assert_split_joins_back(OutlineMode::Skia, MetricsSource::Bounds);
+}
+
+#[test]
+fn split_framed_by_metrics() {
+    assert_split_joins_back(OutlineMode::Skia, MetricsSource::Fixed { ascender: 800., descender: -200. });
+}
+
+#[test]
+fn split_native() {
+    assert_split_joins_back(OutlineMode::Native, MetricsSource::Bounds);
+    assert_split_joins_back(OutlineMode::Native, MetricsSource::Fixed { ascender: 800., descender: -200. });
+}
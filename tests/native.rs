@@ -0,0 +1,71 @@
+use glif2svg::{Converter, MetricsSource, Options, OutlineMode};
+
+/// Two quadratics meeting at (50, 100), which is halfway between their control points, so it's
+/// implied, as if the glif had a run of two off-curve points.
+const QUADRATIC: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="test" format="2">
+  <advance width="500"/>
+  <outline>
+    <contour>
+      <point x="0" y="0" type="line"/>
+      <point x="0" y="100"/>
+      <point x="50" y="100" type="qcurve"/>
+      <point x="100" y="100"/>
+      <point x="100" y="0" type="qcurve"/>
+    </contour>
+  </outline>
+</glyph>"#;
+
+fn native_path_data(optimize: bool) -> String {
+    let glif = glifparser::glif::read(QUADRATIC).unwrap();
+    let mut options = Options::new();
+    options.outline = OutlineMode::Native;
+    options.optimize_paths = optimize;
+    let converter = Converter::new(options);
+    converter.path_data(converter.unframed_pen(), &glif).unwrap()
+}
+
+#[test]
+fn implied_on_curve_point_as_t() {
+    assert_eq!(native_path_data(false), "M 0 0Q 0 -100 50 -100T 100 0Z");
+}
+
+#[test]
+fn implied_on_curve_point_as_t_optimized() {
+    assert_eq!(native_path_data(true), "M0 0Q0-100 50-100T100 0Z");
+}
+
+/// A rectangle from (100, 100) to (300, 200).
+const RECTANGLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="test" format="2">
+  <advance width="500"/>
+  <outline>
+    <contour>
+      <point x="100" y="100" type="line"/>
+      <point x="300" y="100" type="line"/>
+      <point x="300" y="200" type="line"/>
+      <point x="100" y="200" type="line"/>
+    </contour>
+  </outline>
+</glyph>"#;
+
+fn glyph_path_data(metrics: MetricsSource) -> String {
+    let glif = glifparser::glif::read(RECTANGLE).unwrap();
+    let mut options = Options::new();
+    options.outline = OutlineMode::Native;
+    options.metrics = metrics;
+    let svg = Converter::new(options).to_element(&glif).unwrap();
+    let glyph = svg.get_child("g").unwrap();
+    glyph.get_child("path").unwrap().attributes["d"].clone()
+}
+
+#[test]
+fn framed_by_bounds() {
+    assert_eq!(glyph_path_data(MetricsSource::Bounds), "M 100 -100L 300 -100L 300 -200L 100 -200Z");
+}
+
+#[test]
+fn framed_by_metrics() {
+    // The ascender, 800, is at the top of the viewBox, -200.
+    assert_eq!(glyph_path_data(MetricsSource::Fixed { ascender: 800., descender: -200. }), "M 100 500L 300 500L 300 400L 100 400Z");
+}
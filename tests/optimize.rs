@@ -0,0 +1,86 @@
+use glif2svg::svg_path::{self, Segment};
+use glif2svg::{Converter, Options, OutlineMode};
+
+/// Lines in every direction, a smooth cubic, quadratics, and an open contour, at coordinates that
+/// need rounding.
+const GLIF: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="test" format="2">
+  <advance width="500"/>
+  <outline>
+    <contour>
+      <point x="10.5" y="0" type="line"/>
+      <point x="300.25" y="0" type="line"/>
+      <point x="300.25" y="200.125" type="line"/>
+      <point x="250" y="300"/>
+      <point x="150" y="300"/>
+      <point x="100" y="200.125" type="curve" smooth="yes"/>
+      <point x="50" y="100"/>
+      <point x="10.5" y="100"/>
+      <point x="10.5" y="50" type="curve"/>
+    </contour>
+    <contour>
+      <point x="400" y="0" type="line"/>
+      <point x="400" y="100"/>
+      <point x="450" y="100" type="qcurve"/>
+      <point x="500" y="100"/>
+      <point x="500" y="0" type="qcurve"/>
+    </contour>
+    <contour>
+      <point x="0.33333" y="-100" type="move"/>
+      <point x="499.66667" y="-100.5" type="line"/>
+    </contour>
+  </outline>
+</glyph>"#;
+
+fn path_data(outline: OutlineMode, optimize: bool, precision: u8) -> String {
+    let glif = glifparser::glif::read(GLIF).unwrap();
+    let mut options = Options::new();
+    options.outline = outline;
+    options.optimize_paths = optimize;
+    options.precision = precision;
+    let converter = Converter::new(options);
+    converter.path_data(converter.unframed_pen(), &glif).unwrap()
+}
+
+fn points(segment: &Segment) -> Vec<(f32, f32)> {
+    match *segment {
+        Segment::Move(p) | Segment::Line(p) => vec![p],
+        Segment::Quad(q, p) => vec![q, p],
+        Segment::Cubic(c1, c2, p) => vec![c1, c2, p],
+        Segment::Close => vec![],
+    }
+}
+
+fn assert_same_segments(outline: OutlineMode, precision: u8) {
+    let plain = path_data(outline, false, precision);
+    let optimized = path_data(outline, true, precision);
+    assert!(optimized.len() < plain.len(), "{} isn't shorter than {}", optimized, plain);
+
+    let plain_segments = svg_path::parse_path_data(&plain).unwrap();
+    let optimized_segments = svg_path::parse_path_data(&optimized).unwrap();
+    assert_eq!(plain_segments.len(), optimized_segments.len(), "{} and {}", plain, optimized);
+    // Relative coordinates are from the points as written, so they add up to the same points.
+    let tolerance = 1e-3;
+    for (a, b) in plain_segments.iter().zip(optimized_segments.iter()) {
+        assert_eq!(std::mem::discriminant(a), std::mem::discriminant(b), "{:?} and {:?}", a, b);
+        for (p, q) in points(a).into_iter().zip(points(b)) {
+            assert!((p.0 - q.0).abs() < tolerance && (p.1 - q.1).abs() < tolerance, "{:?} and {:?}", a, b);
+        }
+    }
+}
+
+#[test]
+fn optimized_skia_path_data_reads_back_the_same() {
+    assert_same_segments(OutlineMode::Skia, 4);
+}
+
+#[test]
+fn optimized_native_path_data_reads_back_the_same() {
+    assert_same_segments(OutlineMode::Native, 4);
+}
+
+#[test]
+fn optimized_integer_path_data_reads_back_the_same() {
+    assert_same_segments(OutlineMode::Skia, 0);
+    assert_same_segments(OutlineMode::Native, 0);
+}
@@ -0,0 +1,92 @@
+use glif2svg::specimen::{specimen, Kerning};
+use glif2svg::{Converter, Options, Ufo};
+
+use std::fs;
+
+const PLIST_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">"#;
+
+/// A UFO whose glyphs are each a rectangle 700 high, 50 in from either side of its advance, and
+/// whose kerning has a pair of every kind between `A`, `Aacute` (in `A`'s first group) and `V`,
+/// `W` (in `V`'s second group).
+fn ufo(name: &str) -> Ufo {
+    let ufo_dir = std::env::temp_dir().join(format!("glif2svg-test-{}-{}.ufo", name, std::process::id()));
+    let glyphs_dir = ufo_dir.join("glyphs");
+    fs::create_dir_all(&glyphs_dir).unwrap();
+    let glyphs = [("A", 600), ("Aacute", 600), ("V", 500), ("W", 700)];
+    let contents: String = glyphs.iter().map(|(name, _)|format!("<key>{0}</key><string>{0}.glif</string>", name)).collect();
+    fs::write(glyphs_dir.join("contents.plist"), format!("{}<plist version=\"1.0\"><dict>{}</dict></plist>", PLIST_HEADER, contents)).unwrap();
+    for (name, width) in glyphs {
+        fs::write(glyphs_dir.join(format!("{}.glif", name)), format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="{name}" format="2">
+  <advance width="{width}"/>
+  <outline>
+    <contour>
+      <point x="50" y="0" type="line"/>
+      <point x="{right}" y="0" type="line"/>
+      <point x="{right}" y="700" type="line"/>
+      <point x="50" y="700" type="line"/>
+    </contour>
+  </outline>
+</glyph>
+"#, name=name, width=width, right=width - 50)).unwrap();
+    }
+    fs::write(ufo_dir.join("groups.plist"), format!(r#"{}<plist version="1.0"><dict>
+  <key>public.kern1.A</key><array><string>A</string><string>Aacute</string></array>
+  <key>public.kern2.V</key><array><string>V</string><string>W</string></array>
+</dict></plist>"#, PLIST_HEADER)).unwrap();
+    fs::write(ufo_dir.join("kerning.plist"), format!(r#"{}<plist version="1.0"><dict>
+  <key>A</key><dict>
+    <key>V</key><integer>-10</integer>
+    <key>public.kern2.V</key><integer>-20</integer>
+  </dict>
+  <key>public.kern1.A</key><dict>
+    <key>V</key><integer>-30</integer>
+    <key>public.kern2.V</key><real>-40.5</real>
+  </dict>
+</dict></plist>"#, PLIST_HEADER)).unwrap();
+    Ufo::open(&ufo_dir).unwrap()
+}
+
+#[test]
+fn kerning_precedence() {
+    let kerning = Kerning::from_ufo(&ufo("kerning-precedence")).unwrap();
+    assert_eq!(kerning.value("A", "V"), -10.);
+    assert_eq!(kerning.value("A", "W"), -20.);
+    assert_eq!(kerning.value("Aacute", "V"), -30.);
+    assert_eq!(kerning.value("Aacute", "W"), -40.5);
+    assert_eq!(kerning.value("V", "A"), 0.);
+}
+
+/// Each glyph's `(name, transform)`, and the viewBox.
+fn set(ufo: &Ufo, names: &[&str], kerning: Option<&Kerning>) -> (Vec<(String, String)>, String) {
+    let names: Vec<String> = names.iter().map(|&n|n.to_owned()).collect();
+    let svg = specimen(&Converter::new(Options::new()), ufo, &names, kerning).unwrap();
+    let glyphs = svg.children.iter().filter_map(xmltree::XMLNode::as_element)
+        .map(|g|(g.attributes["data-glyph-name"].clone(), g.attributes["transform"].clone()))
+        .collect();
+    (glyphs, svg.attributes["viewBox"].clone())
+}
+
+fn placed(glyphs: &[(&str, &str)]) -> Vec<(String, String)> {
+    glyphs.iter().map(|(name, transform)|(name.to_string(), transform.to_string())).collect()
+}
+
+#[test]
+fn placement_without_kerning() {
+    let ufo = ufo("specimen-unkerned");
+    let (glyphs, view_box) = set(&ufo, &["A", "V", "Aacute"], None);
+    assert_eq!(glyphs, placed(&[("A", "translate(0)"), ("V", "translate(600)"), ("Aacute", "translate(1100)")]));
+    // The advances' span, and the ink's height
+    assert_eq!(view_box, "0 -700 1700 700");
+}
+
+#[test]
+fn placement_with_kerning() {
+    let ufo = ufo("specimen-kerned");
+    let kerning = Kerning::from_ufo(&ufo).unwrap();
+    let (glyphs, view_box) = set(&ufo, &["A", "V", "Aacute", "W"], Some(&kerning));
+    // A–V is -10, V–Aacute isn't kerned, and Aacute–W is -40.5.
+    assert_eq!(glyphs, placed(&[("A", "translate(0)"), ("V", "translate(590)"), ("Aacute", "translate(1090)"), ("W", "translate(1649.5)")]));
+    assert_eq!(view_box, "0 -700 2349.5 700");
+}
@@ -0,0 +1,211 @@
+use glif2svg::svg2glif::SvgReader;
+use glif2svg::{ComponentMode, ContourMode, Converter, MetricsSource, Options, OutlineMode, Ufo};
+use glifparser::{Handle, PointType};
+
+use std::fs;
+use std::path::PathBuf;
+
+fn glif(outline: &str) -> glifparser::Glif<()> {
+    glifparser::glif::read(&format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="test" format="2">
+  <advance width="500"/>
+  <outline>{}</outline>
+</glyph>"#, outline)).unwrap()
+}
+
+/// A rectangle, a closed curve and an open contour, in glif coordinates. Open contours are written
+/// after closed ones, so they come last to be read back in the same order.
+const OUTLINE: &str = r#"
+    <contour>
+      <point x="100" y="100" type="line"/>
+      <point x="300" y="100" type="line"/>
+      <point x="300" y="200" type="line"/>
+      <point x="100" y="200" type="line"/>
+    </contour>
+    <contour>
+      <point x="0" y="0" type="line"/>
+      <point x="0" y="300"/>
+      <point x="400" y="300"/>
+      <point x="400" y="0" type="curve"/>
+    </contour>
+    <contour>
+      <point x="50" y="-100" type="move"/>
+      <point x="450" y="-50" type="line"/>
+    </contour>
+"#;
+
+fn svg(options: Options, glif: &glifparser::Glif<()>) -> xmltree::Element {
+    let svg = Converter::new(options).to_svg(glif).unwrap();
+    xmltree::Element::parse(svg.as_bytes()).unwrap()
+}
+
+fn handle(h: Handle) -> Option<(f32, f32)> {
+    match h {
+        Handle::At(x, y) => Some((x, y)),
+        Handle::Colocated => None,
+    }
+}
+
+/// Each point's position, type and handles.
+fn points(outline: &glifparser::Outline<()>) -> Vec<Vec<((f32, f32), PointType, Option<(f32, f32)>, Option<(f32, f32)>)>> {
+    outline.iter().map(|contour| {
+        contour.iter().map(|p|((p.x, p.y), p.ptype, handle(p.a), handle(p.b))).collect()
+    }).collect()
+}
+
+fn assert_reads_back(options: Options) {
+    let original = glif(OUTLINE);
+    let svgxml = svg(options, &original);
+    let outline = SvgReader::new(&svgxml).outline().unwrap();
+    assert_eq!(points(&outline), points(original.outline.as_ref().unwrap()));
+}
+
+#[test]
+fn reads_back_points_framed_by_bounds() {
+    assert_reads_back(Options::new());
+}
+
+#[test]
+fn reads_back_points_framed_by_metrics() {
+    let mut options = Options::new();
+    options.metrics = MetricsSource::Fixed { ascender: 800., descender: -200. };
+    assert_reads_back(options);
+}
+
+#[test]
+fn reads_back_optimized_points() {
+    let mut options = Options::new();
+    options.optimize_paths = true;
+    assert_reads_back(options);
+}
+
+#[test]
+fn use_transform_before_position() {
+    // The <use>'s x and y translate its content before its transform scales it.
+    let svgxml = xmltree::Element::parse(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 -100 100 100">
+  <defs><path id="dot" d="M 0 0 L 1 0 L 1 -1 Z"/></defs>
+  <use href="#dot" x="10" transform="scale(2)"/>
+</svg>"#.as_bytes()).unwrap();
+    let outline = SvgReader::new(&svgxml).outline().unwrap();
+    let xs: Vec<f32> = outline[0].iter().map(|p|p.x).collect();
+    assert_eq!(xs, vec![20., 22., 22.]);
+}
+
+/// A glyphs directory with `aacute` and the `acute` it has as a component, under `name` in the
+/// temporary directory.
+fn ufo(name: &str) -> Ufo {
+    let glyphs_dir: PathBuf = std::env::temp_dir().join(format!("glif2svg-test-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&glyphs_dir).unwrap();
+    fs::write(glyphs_dir.join("contents.plist"), r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>acute</key><string>acute.glif</string>
+  <key>aacute</key><string>aacute.glif</string>
+</dict>
+</plist>
+"#).unwrap();
+    fs::write(glyphs_dir.join("acute.glif"), r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="acute" format="2">
+  <advance width="300"/>
+  <outline>
+    <contour>
+      <point x="100" y="500" type="line"/>
+      <point x="200" y="500" type="line"/>
+      <point x="250" y="650" type="line"/>
+    </contour>
+  </outline>
+</glyph>
+"#).unwrap();
+    fs::write(glyphs_dir.join("aacute.glif"), AACUTE).unwrap();
+    Ufo::open(&glyphs_dir).unwrap()
+}
+
+/// A glyph with point names and identifiers, a curve, and a component, none of which survive
+/// being rebuilt from path data alone.
+const AACUTE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="aacute" format="2">
+  <advance width="500"/>
+  <unicode hex="00E1"/>
+  <outline>
+    <contour>
+      <point x="0" y="0" type="line" name="start" identifier="p0"/>
+      <point x="0" y="300"/>
+      <point x="400" y="300"/>
+      <point x="400" y="0" type="curve" smooth="yes" identifier="p1"/>
+    </contour>
+    <component base="acute" xOffset="50"/>
+  </outline>
+</glyph>
+"#;
+
+fn round_trip(options: Options) {
+    let original = glifparser::glif::read(AACUTE).unwrap();
+    let svgxml = svg(options, &original);
+    let read_back = SvgReader::new(&svgxml).to_glif(None).unwrap();
+    assert_eq!(glifparser::glif::write(&read_back).unwrap(), glifparser::glif::write(&original).unwrap());
+}
+
+#[test]
+fn round_trip_flattened_components() {
+    let mut options = Options::new();
+    options.round_trip = true;
+    options.ufo = Some(ufo("round-trip-flatten"));
+    round_trip(options);
+}
+
+#[test]
+fn round_trip_used_components() {
+    let mut options = Options::new();
+    options.round_trip = true;
+    options.components = ComponentMode::Use;
+    options.ufo = Some(ufo("round-trip-use"));
+    round_trip(options);
+}
+
+/// A TrueType contour with a run of two off-curve points, whose on-curve point, (50, 100), is
+/// implied.
+const QUADRATIC: &str = r#"
+    <contour>
+      <point x="0" y="0" type="line"/>
+      <point x="0" y="100"/>
+      <point x="100" y="100"/>
+      <point x="100" y="0" type="qcurve"/>
+    </contour>
+"#;
+
+/// Each `<point>` of a glif's text, as `(x, y, type)`, off-curve points typed `offcurve`.
+fn glif_points(glif_str: &str) -> Vec<(String, String, String)> {
+    let glyphxml = xmltree::Element::parse(glif_str.as_bytes()).unwrap();
+    let outlinexml = glyphxml.get_child("outline").unwrap();
+    outlinexml.children.iter().filter_map(xmltree::XMLNode::as_element)
+        .flat_map(|contour|contour.children.iter().filter_map(xmltree::XMLNode::as_element))
+        .map(|p|(p.attributes["x"].clone(), p.attributes["y"].clone(), p.attributes.get("type").cloned().unwrap_or_else(||"offcurve".to_owned())))
+        .collect()
+}
+
+#[test]
+fn off_curve_run_survives() {
+    let mut options = Options::new();
+    options.outline = OutlineMode::Native;
+    let svgxml = svg(options, &glif(QUADRATIC));
+    let read_back = SvgReader::new(&svgxml).to_glif(None).unwrap();
+    let original = glif_points(&format!("<glyph><outline>{}</outline></glyph>", QUADRATIC));
+    assert_eq!(glif_points(&glifparser::glif::write(&read_back).unwrap()), original);
+}
+
+#[test]
+fn round_trip_deleted_path() {
+    let mut options = Options::new();
+    options.round_trip = true;
+    options.contours = ContourMode::Split;
+    let mut svgxml = svg(options, &glif(OUTLINE));
+    // As deleting the rectangle in Inkscape would
+    let glyphxml = svgxml.get_mut_child("g").unwrap();
+    let rectangle = glyphxml.children.iter().position(|n|n.as_element().is_some_and(|e|e.name == "path")).unwrap();
+    glyphxml.children.remove(rectangle);
+
+    let read_back = SvgReader::new(&svgxml).to_glif(None).unwrap();
+    let outline = read_back.outline.unwrap();
+    assert_eq!(points(&outline), points(&glif(OUTLINE).outline.unwrap()[1..].to_vec()));
+}
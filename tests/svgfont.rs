@@ -0,0 +1,49 @@
+use glif2svg::svgfont::svg_font;
+use glif2svg::{Converter, Options, Ufo};
+
+use std::fs;
+
+const PLIST_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">"#;
+
+/// A UFO of empty glyphs, with kerning of a glyph against a group and of a group against a glyph.
+fn ufo() -> Ufo {
+    let ufo_dir = std::env::temp_dir().join(format!("glif2svg-test-svgfont-{}.ufo", std::process::id()));
+    let glyphs_dir = ufo_dir.join("glyphs");
+    fs::create_dir_all(&glyphs_dir).unwrap();
+    let names = ["A", "V", "W", "T", "o"];
+    let contents: String = names.iter().map(|name|format!("<key>{0}</key><string>{0}.glif</string>", name)).collect();
+    fs::write(glyphs_dir.join("contents.plist"), format!("{}<plist version=\"1.0\"><dict>{}</dict></plist>", PLIST_HEADER, contents)).unwrap();
+    for name in names {
+        fs::write(glyphs_dir.join(format!("{}.glif", name)), format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="{}" format="2">
+  <advance width="500"/>
+</glyph>
+"#, name)).unwrap();
+    }
+    fs::write(ufo_dir.join("groups.plist"), format!(r#"{}<plist version="1.0"><dict>
+  <key>public.kern1.round</key><array><string>o</string></array>
+  <key>public.kern2.V</key><array><string>V</string><string>W</string></array>
+</dict></plist>"#, PLIST_HEADER)).unwrap();
+    fs::write(ufo_dir.join("kerning.plist"), format!(r#"{}<plist version="1.0"><dict>
+  <key>A</key><dict><key>public.kern2.V</key><integer>-50</integer></dict>
+  <key>public.kern1.round</key><dict><key>T</key><real>20.5</real></dict>
+</dict></plist>"#, PLIST_HEADER)).unwrap();
+    Ufo::open(&ufo_dir).unwrap()
+}
+
+#[test]
+fn hkern_groups_and_sign() {
+    let svg = svg_font(&Converter::new(Options::new()), &ufo()).unwrap();
+    let font = svg.get_child("defs").unwrap().get_child("font").unwrap();
+    let hkerns: Vec<(String, String, String)> = font.children.iter()
+        .filter_map(xmltree::XMLNode::as_element)
+        .filter(|e|e.name == "hkern")
+        .map(|e|(e.attributes["g1"].clone(), e.attributes["g2"].clone(), e.attributes["k"].clone()))
+        .collect();
+    // SVG's k is taken off the advance, so it's the UFO's value negated.
+    assert_eq!(hkerns, vec![
+        ("A".to_owned(), "V,W".to_owned(), "50".to_owned()),
+        ("o".to_owned(), "T".to_owned(), "-20.5".to_owned()),
+    ]);
+}
@@ -0,0 +1,113 @@
+use glif2svg::{Converter, MetricsSource, Options, SVGPathPen, ViewBoxMode};
+
+fn glif(width: u64, outline: &str) -> glifparser::Glif<()> {
+    glifparser::glif::read(&format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="test" format="2">
+  <advance width="{}"/>
+  <outline>{}</outline>
+</glyph>"#, width, outline)).unwrap()
+}
+
+/// A rectangle from `(x0, y0)` to `(x1, y1)`, in glif coordinates.
+fn rectangle(x0: i32, y0: i32, x1: i32, y1: i32) -> glifparser::Glif<()> {
+    glif(500, &format!(r#"
+    <contour>
+      <point x="{x0}" y="{y0}" type="line"/>
+      <point x="{x1}" y="{y0}" type="line"/>
+      <point x="{x1}" y="{y1}" type="line"/>
+      <point x="{x0}" y="{y1}" type="line"/>
+    </contour>"#, x0=x0, y0=y0, x1=x1, y1=y1))
+}
+
+fn svg_attribute(options: Options, glif: &glifparser::Glif<()>, name: &str) -> String {
+    let svg = Converter::new(options).to_element(glif).unwrap();
+    svg.attributes.get(name).cloned().unwrap_or_else(||panic!("No {} attribute", name))
+}
+
+fn bounds_view_box(glif: &glifparser::Glif<()>) -> String {
+    svg_attribute(Options::new(), glif, "viewBox")
+}
+
+fn metrics_view_box(glif: &glifparser::Glif<()>, ascender: f64, descender: f64) -> String {
+    let mut options = Options::new();
+    options.metrics = MetricsSource::Fixed { ascender, descender };
+    svg_attribute(options, glif, "viewBox")
+}
+
+// The SVG's y axis points down, so the glif's y ∈ [y0, y1] is the SVG's y ∈ [-y1, -y0].
+
+#[test]
+fn first_quadrant() {
+    assert_eq!(bounds_view_box(&rectangle(100, 100, 300, 200)), "100 -200 200 100");
+}
+
+#[test]
+fn second_quadrant() {
+    assert_eq!(bounds_view_box(&rectangle(-300, 100, -100, 200)), "-300 -200 200 100");
+}
+
+#[test]
+fn third_quadrant() {
+    assert_eq!(bounds_view_box(&rectangle(-300, -200, -100, -100)), "-300 100 200 100");
+}
+
+#[test]
+fn fourth_quadrant() {
+    assert_eq!(bounds_view_box(&rectangle(100, -200, 300, -100)), "100 100 200 100");
+}
+
+#[test]
+fn around_origin() {
+    assert_eq!(bounds_view_box(&rectangle(-100, -50, 100, 50)), "-100 -50 200 100");
+}
+
+#[test]
+fn width_height() {
+    let mut options = Options::new();
+    options.viewbox = ViewBoxMode::WidthHeight;
+    let glif = rectangle(100, 100, 300, 200);
+    assert_eq!(svg_attribute(options.clone(), &glif, "width"), "200px");
+    assert_eq!(svg_attribute(options, &glif, "height"), "100px");
+}
+
+#[test]
+fn empty_glyph() {
+    let glif = glif(500, "");
+    assert_eq!(bounds_view_box(&glif), "0 0 0 0");
+    assert_eq!(metrics_view_box(&glif, 800., -200.), "0 -200 500 1000");
+}
+
+#[test]
+fn metrics() {
+    assert_eq!(metrics_view_box(&rectangle(100, 100, 300, 200), 800., -200.), "0 -200 500 1000");
+}
+
+#[test]
+fn positive_descender() {
+    assert_eq!(metrics_view_box(&rectangle(100, 100, 300, 200), 900., 100.), "0 100 500 800");
+}
+
+#[test]
+fn negative_advance_width() {
+    // As a glyph with an advance of -250 would be framed
+    let mut pen = SVGPathPen::new();
+    pen.minx = 0.;
+    pen.maxx = -250.;
+    pen.miny = -200.;
+    pen.maxy = 800.;
+    assert_eq!(pen.viewBox(), (-250., -200., 250., 1000.));
+    assert_eq!(pen.viewBox_str(), "-250 -200 250 1000");
+}
+
+#[test]
+fn handles_outside_curve() {
+    // The curve's top is at 3/4 of its control points' height, 225, not at 300.
+    let glif = glif(500, r#"
+    <contour>
+      <point x="0" y="0" type="line"/>
+      <point x="0" y="300"/>
+      <point x="400" y="300"/>
+      <point x="400" y="0" type="curve"/>
+    </contour>"#);
+    assert_eq!(bounds_view_box(&glif), "0 -225 400 225");
+}